@@ -0,0 +1,235 @@
+use crate::prelude::*;
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+
+/// A 2-dimensional axis-aligned bounding box.
+///
+/// Boxes are closed, points on the boundary are contained and touching boxes intersect.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QAabb2 {
+    pub min: QVec2,
+    pub max: QVec2,
+}
+
+impl QAabb2 {
+    /// Creates a new box.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `min` is greater than `max`.
+    #[inline]
+    #[must_use]
+    pub fn new(min: QVec2, max: QVec2) -> Self {
+        assert!(min.x <= max.x && min.y <= max.y, "[QAabb2::new] Expected min <= max.");
+        Self { min, max }
+    }
+
+    /// Creates a box from its center and half extents.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn from_center_half_extents(center: QVec2, half_extents: QVec2) -> Self {
+        let half_extents = half_extents.abs();
+        Self::new(center.saturating_sub(half_extents), center.saturating_add(half_extents))
+    }
+
+    /// Creates the smallest box containing all `points`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let aabb = QAabb2::from_points(&[qvec2!(1, -2), qvec2!(-3, 4), qvec2!(0, 0)]);
+    /// assert_eq!(aabb, QAabb2::new(qvec2!(-3, -2), qvec2!(1, 4)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `points` is empty.
+    #[must_use]
+    pub fn from_points(points: &[QVec2]) -> Self {
+        assert!(!points.is_empty(), "[QAabb2::from_points] Expected at least one point.");
+        let min = points.iter().fold(QVec2::MAX, |acc, p| acc.min(*p));
+        let max = points.iter().fold(QVec2::MIN, |acc, p| acc.max(*p));
+        Self { min, max }
+    }
+
+    /// Returns whether `point` is inside the box or on its boundary.
+    #[inline]
+    #[must_use]
+    pub fn contains_point(&self, point: QVec2) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x && point.y >= self.min.y && point.y <= self.max.y
+    }
+
+    /// Returns whether `other` is entirely inside the box.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, other: &Self) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// Returns whether the boxes overlap or touch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let a = QAabb2::new(qvec2!(0, 0), qvec2!(2, 2));
+    /// assert!(a.intersects(&QAabb2::new(qvec2!(1, 1), qvec2!(3, 3))));
+    /// assert!(a.intersects(&QAabb2::new(qvec2!(2, 0), qvec2!(3, 1))));
+    /// assert!(!a.intersects(&QAabb2::new(qvec2!(2.5, 0), qvec2!(3, 1))));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    /// Returns the smallest box containing both boxes.
+    #[inline]
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Returns the overlapping part of the boxes, or `None` if they don't intersect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let a = QAabb2::new(qvec2!(0, 0), qvec2!(2, 2));
+    /// let b = QAabb2::new(qvec2!(1, -1), qvec2!(3, 1));
+    /// assert_eq!(a.intersection(&b), Some(QAabb2::new(qvec2!(1, 0), qvec2!(2, 1))));
+    /// assert_eq!(a.intersection(&QAabb2::new(qvec2!(5, 5), qvec2!(6, 6))), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if !self.intersects(other) {
+            return None;
+        }
+        Some(Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        })
+    }
+
+    /// Returns the box grown by `margin` on every side.
+    ///
+    /// A negative `margin` shrinks the box, down to its center at most.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn expand(&self, margin: Q64) -> Self {
+        let min = self.min.saturating_sub_num(margin);
+        let max = self.max.saturating_add_num(margin);
+        if min.x <= max.x && min.y <= max.y {
+            return Self { min, max };
+        }
+        let center = self.center();
+        Self {
+            min: QVec2::new(min.x.min(center.x), min.y.min(center.y)),
+            max: QVec2::new(max.x.max(center.x), max.y.max(center.y)),
+        }
+    }
+
+    /// Returns the smallest box containing the box and `point`.
+    #[inline]
+    #[must_use]
+    pub fn expand_to_point(&self, point: QVec2) -> Self {
+        Self {
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+
+    /// Returns the center of the box.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn center(&self) -> QVec2 {
+        self.min.saturating_add(self.half_extents())
+    }
+
+    /// Returns the size of the box.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn size(&self) -> QVec2 {
+        self.max.saturating_sub(self.min)
+    }
+
+    /// Returns half the size of the box.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn half_extents(&self) -> QVec2 {
+        self.size().saturating_mul_num(Q64::HALF)
+    }
+
+    /// Returns the area of the box.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn area(&self) -> Q64 {
+        let size = self.size();
+        size.x.saturating_mul(size.y)
+    }
+
+    /// Returns the perimeter of the box.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn perimeter(&self) -> Q64 {
+        let size = self.size();
+        size.x.saturating_add(size.y).saturating_mul(Q64::TWO)
+    }
+
+    /// Returns the point of the box closest to `point`, which is `point` itself if it is inside.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let aabb = QAabb2::new(qvec2!(0, 0), qvec2!(2, 2));
+    /// assert_eq!(aabb.closest_point(qvec2!(5, 1)), qvec2!(2, 1));
+    /// assert_eq!(aabb.closest_point(qvec2!(1, 1)), qvec2!(1, 1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn closest_point(&self, point: QVec2) -> QVec2 {
+        point.clamp(self.min, self.max)
+    }
+
+    /// Returns the squared distance from `point` to the box, zero if it is inside.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn distance_squared_to_point(&self, point: QVec2) -> Q64 {
+        self.closest_point(point).distance_squared(point)
+    }
+}
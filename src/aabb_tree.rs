@@ -0,0 +1,451 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::ray::{QRay2, QRayHit, QRaycast};
+use serde::{Deserialize, Serialize};
+
+/// A handle to a leaf of a [`QAabbTree`].
+///
+/// Keys of removed leaves are never handed out again by the same tree, so a stale key is simply
+/// not found. Nodes are recycled for leaves and internal nodes alike, except that a node whose
+/// counter has reached its last value is never allocated again.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct QAabbTreeKey {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    /// The fat box of a leaf, or the union of the children.
+    aabb: QAabb2,
+    parent: Option<usize>,
+    /// Only meaningful for internal nodes.
+    children: [usize; 2],
+    /// Zero for leaves.
+    height: u32,
+    /// Incremented when the node is released, or when `clear` drops its leaf. Never allocated
+    /// again once `u32::MAX`.
+    generation: u32,
+    /// `Some` for leaves only.
+    value: Option<T>,
+}
+
+impl<T> Node<T> {
+    fn is_leaf(&self) -> bool {
+        self.height == 0
+    }
+}
+
+/// A dynamic bounding volume hierarchy.
+///
+/// Leaves store boxes enlarged by a margin, so objects moving a little don't touch the tree. New
+/// leaves go where the total perimeter of the tree grows least, and AVL rotations keep it
+/// balanced, following Box2D's `b2DynamicTree`. All decisions use exact fixed-point comparisons
+/// with fixed tie-breaking, so the same sequence of operations always builds the same tree.
+#[derive(Debug, Clone)]
+pub struct QAabbTree<T> {
+    nodes: Vec<Node<T>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    margin: Q64,
+    len: usize,
+}
+
+impl<T> QAabbTree<T> {
+    /// Creates an empty tree enlarging leaf boxes by `margin` on every side.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `margin` is negative.
+    #[must_use]
+    pub fn new(margin: Q64) -> Self {
+        assert!(margin >= Q64::ZERO, "[QAabbTree::new] Expected margin >= 0.");
+        Self { nodes: Vec::new(), free: Vec::new(), root: None, margin, len: 0 }
+    }
+
+    /// Returns the number of leaves.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there are no leaves.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the height of the tree, zero for an empty tree or a single leaf.
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.root.map_or(0, |root| self.nodes[root].height)
+    }
+
+    /// Inserts a leaf for `value` bounded by `aabb` and returns its key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::aabb_tree::QAabbTree;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut tree = QAabbTree::new(q64!(0.1));
+    /// let ground = tree.insert(QAabb2::new(qvec2!(-1000, -1), qvec2!(1000, 0)), "ground");
+    /// let pebble = tree.insert(QAabb2::new(qvec2!(2, 0), qvec2!(2.01, 0.01)), "pebble");
+    /// let bird = tree.insert(QAabb2::new(qvec2!(0, 50), qvec2!(1, 51)), "bird");
+    ///
+    /// let found: Vec<_> = tree.query_aabb(QAabb2::new(qvec2!(1, -1), qvec2!(3, 1))).map(|(_, v)| *v).collect();
+    /// assert_eq!(found, ["ground", "pebble"]);
+    /// assert_eq!(tree.pairs(), [(ground, pebble)]);
+    ///
+    /// // Small moves stay inside the fat box.
+    /// assert!(!tree.update(bird, QAabb2::new(qvec2!(0.05, 50), qvec2!(1.05, 51))));
+    /// assert!(tree.update(bird, QAabb2::new(qvec2!(0, 0), qvec2!(1, 1))));
+    /// assert_eq!(tree.pairs(), [(ground, pebble), (ground, bird)]);
+    ///
+    /// assert_eq!(tree.remove(pebble), Some("pebble"));
+    /// assert_eq!(tree.remove(pebble), None);
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn insert(&mut self, aabb: QAabb2, value: T) -> QAabbTreeKey {
+        let leaf = self.allocate(aabb.expand(self.margin), Some(value));
+        self.insert_leaf(leaf);
+        self.len += 1;
+        QAabbTreeKey { index: leaf as u32, generation: self.nodes[leaf].generation }
+    }
+
+    /// Removes the leaf of `key` and returns its value, or `None` if it isn't present.
+    pub fn remove(&mut self, key: QAabbTreeKey) -> Option<T> {
+        let leaf = self.leaf_index(key)?;
+        self.remove_leaf(leaf);
+        let value = self.nodes[leaf].value.take();
+        self.release(leaf);
+        self.len -= 1;
+        value
+    }
+
+    /// Moves the leaf of `key` to `aabb`, returning whether it was reinserted.
+    ///
+    /// The leaf is only reinserted when `aabb` leaves its fat box, or when the fat box has become
+    /// much larger than needed. Returns `false` if `key` isn't present.
+    ///
+    /// SAT
+    pub fn update(&mut self, key: QAabbTreeKey, aabb: QAabb2) -> bool {
+        let Some(leaf) = self.leaf_index(key) else {
+            return false;
+        };
+        let fat = self.nodes[leaf].aabb;
+        let huge = aabb.expand(self.margin.saturating_mul(Q64::from_num(4)));
+        if fat.contains(&aabb) && huge.contains(&fat) {
+            return false;
+        }
+
+        self.remove_leaf(leaf);
+        self.nodes[leaf].aabb = aabb.expand(self.margin);
+        self.insert_leaf(leaf);
+        true
+    }
+
+    /// Removes all leaves. Keys handed out before stay invalid.
+    pub fn clear(&mut self) {
+        for node in &mut self.nodes {
+            if node.value.take().is_some() {
+                node.generation += 1;
+            }
+            node.height = 0;
+        }
+        // Hands out the lowest indices first.
+        self.free = (0..self.nodes.len()).rev().filter(|&i| self.nodes[i].generation < u32::MAX).collect();
+        self.root = None;
+        self.len = 0;
+    }
+
+    /// Returns the value of the leaf of `key`.
+    #[must_use]
+    pub fn get(&self, key: QAabbTreeKey) -> Option<&T> {
+        self.nodes[self.leaf_index(key)?].value.as_ref()
+    }
+
+    /// Returns the value of the leaf of `key` mutably.
+    #[must_use]
+    pub fn get_mut(&mut self, key: QAabbTreeKey) -> Option<&mut T> {
+        let leaf = self.leaf_index(key)?;
+        self.nodes[leaf].value.as_mut()
+    }
+
+    /// Returns the fat box of the leaf of `key`.
+    #[must_use]
+    pub fn fat_aabb(&self, key: QAabbTreeKey) -> Option<QAabb2> {
+        Some(self.nodes[self.leaf_index(key)?].aabb)
+    }
+
+    /// Iterates over all leaves by key, in the order of [`QAabbTreeKey`].
+    pub fn iter(&self) -> impl Iterator<Item = (QAabbTreeKey, &T)> + '_ {
+        self.nodes.iter().enumerate().filter_map(|(index, node)| {
+            let value = node.value.as_ref()?;
+            Some((QAabbTreeKey { index: index as u32, generation: node.generation }, value))
+        })
+    }
+
+    /// Iterates over the leaves whose fat box intersects `aabb`, depth first.
+    pub fn query_aabb(&self, aabb: QAabb2) -> impl Iterator<Item = (QAabbTreeKey, &T)> + '_ {
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        core::iter::from_fn(move || {
+            while let Some(index) = stack.pop() {
+                let node = &self.nodes[index];
+                if !node.aabb.intersects(&aabb) {
+                    continue;
+                }
+                if let Some(value) = &node.value {
+                    return Some((QAabbTreeKey { index: index as u32, generation: node.generation }, value));
+                }
+                if !node.is_leaf() {
+                    stack.push(node.children[1]);
+                    stack.push(node.children[0]);
+                }
+            }
+            None
+        })
+    }
+
+    /// Returns every pair of leaves whose fat boxes intersect.
+    ///
+    /// Each pair appears once, with the lower key first, sorted by the first key and then by the
+    /// order of [`QAabbTree::query_aabb`].
+    #[must_use]
+    pub fn pairs(&self) -> Vec<(QAabbTreeKey, QAabbTreeKey)> {
+        let mut pairs = Vec::new();
+        for (key, _) in self.iter() {
+            let aabb = self.nodes[key.index as usize].aabb;
+            pairs.extend(self.query_aabb(aabb).filter(|(other, _)| key.index < other.index).map(|(other, _)| (key, other)));
+        }
+        pairs
+    }
+
+    /// Returns the nearest hit of `ray` for `t` in `[0, max_t]`, along with its leaf.
+    ///
+    /// `hit` tests the actual shape of a leaf against the ray up to the given `max_t`, which shrinks
+    /// as closer hits are found so that subtrees behind them are skipped. On equal `t`, the leaf
+    /// found first in depth-first order wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb_tree::QAabbTree;
+    /// use qmath::ray::{QRay2, QRaycast};
+    /// use qmath::shapes::QCircle;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut tree = QAabbTree::new(q64!(0.1));
+    /// for x in [3, 8, 5] {
+    ///     let circle = QCircle::new(qvec2!(x, 0), q64!(1));
+    ///     tree.insert(circle.aabb(), circle);
+    /// }
+    ///
+    /// let ray = QRay2::new(qvec2!(0, 0), QVec2::X);
+    /// let (key, hit) = tree.raycast(&ray, q64!(100), |_, circle, max_t| circle.raycast(&ray, max_t)).unwrap();
+    /// assert_eq!((tree.get(key).unwrap().center, hit.t), (qvec2!(3, 0), q64!(2)));
+    ///
+    /// let up = QRay2::new(qvec2!(0, 0), QVec2::Y);
+    /// assert!(tree.raycast(&up, q64!(100), |_, circle, max_t| circle.raycast(&up, max_t)).is_none());
+    /// ```
+    pub fn raycast<F>(&self, ray: &QRay2, max_t: Q64, mut hit: F) -> Option<(QAabbTreeKey, QRayHit)>
+    where
+        F: FnMut(QAabbTreeKey, &T, Q64) -> Option<QRayHit>,
+    {
+        let mut best: Option<(QAabbTreeKey, QRayHit)> = None;
+        let mut max_t = max_t;
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.aabb.raycast(ray, max_t).is_none() {
+                continue;
+            }
+            if let Some(value) = &node.value {
+                let key = QAabbTreeKey { index: index as u32, generation: node.generation };
+                if let Some(h) = hit(key, value, max_t)
+                    && h.t <= max_t
+                    && best.is_none_or(|(_, b)| h.t < b.t)
+                {
+                    max_t = h.t;
+                    best = Some((key, h));
+                }
+            } else if !node.is_leaf() {
+                stack.push(node.children[1]);
+                stack.push(node.children[0]);
+            }
+        }
+        best
+    }
+
+    fn leaf_index(&self, key: QAabbTreeKey) -> Option<usize> {
+        let node = self.nodes.get(key.index as usize)?;
+        (node.generation == key.generation && node.value.is_some()).then_some(key.index as usize)
+    }
+
+    fn allocate(&mut self, aabb: QAabb2, value: Option<T>) -> usize {
+        let node = Node { aabb, parent: None, children: [0; 2], height: 0, generation: 0, value };
+        match self.free.pop() {
+            Some(index) => {
+                let generation = self.nodes[index].generation;
+                self.nodes[index] = Node { generation, ..node };
+                index
+            }
+            None => {
+                assert!(self.nodes.len() < u32::MAX as usize, "[QAabbTree::allocate] Too many nodes.");
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, index: usize) {
+        let node = &mut self.nodes[index];
+        node.value = None;
+        node.height = 0;
+        node.generation += 1;
+        if node.generation < u32::MAX {
+            self.free.push(index);
+        }
+    }
+
+    /// Replaces `old` by `new` among the children of `parent`, or as the root.
+    fn replace_child(&mut self, parent: Option<usize>, old: usize, new: usize) {
+        match parent {
+            Some(parent) => {
+                let children = &mut self.nodes[parent].children;
+                if children[0] == old {
+                    children[0] = new;
+                } else {
+                    children[1] = new;
+                }
+            }
+            None => self.root = Some(new),
+        }
+    }
+
+    /// Picks the sibling by the perimeter heuristic of Box2D.
+    ///
+    /// SAT
+    fn insert_leaf(&mut self, leaf: usize) {
+        let Some(root) = self.root else {
+            self.root = Some(leaf);
+            self.nodes[leaf].parent = None;
+            return;
+        };
+
+        let leaf_aabb = self.nodes[leaf].aabb;
+        let mut index = root;
+        while !self.nodes[index].is_leaf() {
+            let node = &self.nodes[index];
+            let area = node.aabb.perimeter();
+            let combined = node.aabb.union(&leaf_aabb).perimeter();
+
+            // Cost of making a new parent for this node and the leaf, and the cost pushed down to
+            // the children by growing this node.
+            let cost = combined.saturating_mul(Q64::TWO);
+            let inheritance = combined.saturating_sub(area).saturating_mul(Q64::TWO);
+            let child_cost = |child: usize| {
+                let aabb = self.nodes[child].aabb;
+                let grown = aabb.union(&leaf_aabb).perimeter();
+                let grown = if self.nodes[child].is_leaf() { grown } else { grown.saturating_sub(aabb.perimeter()) };
+                grown.saturating_add(inheritance)
+            };
+            let [child1, child2] = node.children;
+            let (cost1, cost2) = (child_cost(child1), child_cost(child2));
+
+            if cost < cost1 && cost < cost2 {
+                break;
+            }
+            index = if cost1 < cost2 { child1 } else { child2 };
+        }
+
+        let sibling = index;
+        let old_parent = self.nodes[sibling].parent;
+        let new_parent = self.allocate(leaf_aabb.union(&self.nodes[sibling].aabb), None);
+        self.nodes[new_parent].parent = old_parent;
+        self.nodes[new_parent].children = [sibling, leaf];
+        self.nodes[new_parent].height = self.nodes[sibling].height + 1;
+        self.replace_child(old_parent, sibling, new_parent);
+        self.nodes[sibling].parent = Some(new_parent);
+        self.nodes[leaf].parent = Some(new_parent);
+
+        self.refit(Some(new_parent));
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        if self.root == Some(leaf) {
+            self.root = None;
+            return;
+        }
+
+        let parent = self.nodes[leaf].parent.expect("[QAabbTree::remove_leaf] Expected a parent.");
+        let grand_parent = self.nodes[parent].parent;
+        let [child1, child2] = self.nodes[parent].children;
+        let sibling = if child1 == leaf { child2 } else { child1 };
+
+        self.replace_child(grand_parent, parent, sibling);
+        self.nodes[sibling].parent = grand_parent;
+        self.release(parent);
+        self.refit(grand_parent);
+    }
+
+    /// Rebalances and recomputes the boxes and heights from `index` up to the root.
+    fn refit(&mut self, mut index: Option<usize>) {
+        while let Some(i) = index {
+            let i = self.balance(i);
+            let [child1, child2] = self.nodes[i].children;
+            self.nodes[i].height = 1 + self.nodes[child1].height.max(self.nodes[child2].height);
+            self.nodes[i].aabb = self.nodes[child1].aabb.union(&self.nodes[child2].aabb);
+            index = self.nodes[i].parent;
+        }
+    }
+
+    /// Rotates a child up if the subtrees of `a` differ in height by more than one, returning the
+    /// new root of the subtree.
+    fn balance(&mut self, a: usize) -> usize {
+        if self.nodes[a].is_leaf() || self.nodes[a].height < 2 {
+            return a;
+        }
+
+        let [b, c] = self.nodes[a].children;
+        let (height_b, height_c) = (self.nodes[b].height, self.nodes[c].height);
+        if height_c > height_b + 1 {
+            self.rotate_up(a, c, 1);
+            c
+        } else if height_b > height_c + 1 {
+            self.rotate_up(a, b, 0);
+            b
+        } else {
+            a
+        }
+    }
+
+    /// Makes `up`, the child of `a` on `side`, the parent of `a`. The taller child of `up` stays
+    /// with it and the shorter one replaces `up` under `a`.
+    fn rotate_up(&mut self, a: usize, up: usize, side: usize) {
+        let other = self.nodes[a].children[1 - side];
+        let [f, g] = self.nodes[up].children;
+        let (keep, give) = if self.nodes[f].height > self.nodes[g].height { (f, g) } else { (g, f) };
+
+        let parent = self.nodes[a].parent;
+        self.nodes[up].parent = parent;
+        self.replace_child(parent, a, up);
+        self.nodes[a].parent = Some(up);
+
+        self.nodes[up].children = [a, keep];
+        self.nodes[a].children[side] = give;
+        self.nodes[give].parent = Some(a);
+
+        self.nodes[a].aabb = self.nodes[other].aabb.union(&self.nodes[give].aabb);
+        self.nodes[a].height = 1 + self.nodes[other].height.max(self.nodes[give].height);
+        self.nodes[up].aabb = self.nodes[a].aabb.union(&self.nodes[keep].aabb);
+        self.nodes[up].height = 1 + self.nodes[a].height.max(self.nodes[keep].height);
+    }
+}
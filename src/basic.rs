@@ -1,5 +1,6 @@
 use crate::Q64;
 use fixed::traits::Fixed;
+use fixed::types::I64F64;
 
 /// Elementary functions.
 pub trait QBasic: Fixed {
@@ -32,6 +33,8 @@ pub trait QBasic: Fixed {
     fn powi(self, n: isize) -> Self;
     /// Computes and returns the exponential function of a number.
     fn exp(self) -> Self;
+    /// Computes and returns the natural logarithm of a positive number.
+    fn ln(self) -> Self;
     /// Half of a number.
     fn half(self) -> Self;
 }
@@ -102,7 +105,7 @@ impl QBasic for Q64 {
     
         while abs_exponent > 0 {
             if abs_exponent == 1 {
-                result = result * current_power;
+                result *= current_power;
                 break;
             }
             current_power = current_power * current_power;
@@ -116,22 +119,99 @@ impl QBasic for Q64 {
         result
     }
     
+    /// Saturates to `MAX` above `ln(MAX)` and flushes to zero below `ln(DELTA)`.
+    ///
     /// # Examples
     /// 
     /// ```rust
     /// use qmath::prelude::*;
     /// let e = Q64::ONE.exp();
     /// assert!(e.abs_diff(Q64::E) <= Q64::EPS);
+    /// assert!(Q64::LN_2.exp().abs_diff(Q64::TWO) <= Q64::EPS);
+    /// assert!(q64!(-10).exp().abs_diff(q64!(0.0000453999)) <= Q64::EPS);
+    /// assert!(q64!(20).exp().abs_diff(q64!(485165195.40979)) <= Q64::ONE);
+    /// assert!(q64!(21.3).exp().abs_diff(q64!(1780215034.76)) <= Q64::ONE);
+    /// assert_eq!(q64!(21.6).exp(), Q64::MAX);
+    /// assert_eq!(q64!(22).exp(), Q64::MAX);
+    /// assert_eq!(Q64::MAX.exp(), Q64::MAX);
+    /// assert_eq!(q64!(-23).exp(), Q64::ZERO);
+    /// assert_eq!(Q64::MIN.exp(), Q64::ZERO);
     /// ```
+    ///
+    /// SAT
     fn exp(self) -> Self {
+        // self = k * ln(2) + r, |r| <= ln(2) / 2, so exp(self) = 2^k * exp(r).
+        let k = (I64F64::from_num(self) / I64F64::LN_2).round().to_num::<i64>();
+        if k > 31 {
+            return Self::MAX;
+        }
+        if k < -33 {
+            return Self::ZERO;
+        }
+        // Reduced with a 64-bit fraction, as the error of ln(2) is scaled by k.
+        let r = (I64F64::from_num(self) - I64F64::LN_2 * i128::from(k)).to_num::<Self>();
+
         let mut rst = Self::ONE;
         let mut term = Self::ONE;
         for i in 1..=12 {
-            let temp = self / i;
-            term = term * temp;
-            rst = rst + term;
+            term = term * r / i;
+            rst += term;
+        }
+
+        if k >= 0 {
+            // Scaled in the wide type, as 2^31 * exp(r) may not fit.
+            Self::saturating_from_num(I64F64::from_num(rst) << k as u32)
+        } else {
+            rst >> -k as u32
         }
-        rst
+    }
+
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use qmath::prelude::*;
+    /// assert!(Q64::E.ln().abs_diff(Q64::ONE) <= Q64::EPS);
+    /// assert!(Q64::TWO.ln().abs_diff(Q64::LN_2) <= Q64::EPS);
+    /// assert!(Q64::DELTA.ln().abs_diff(-Q64::LN_2 * 32) <= Q64::EPS);
+    /// assert!(Q64::MAX.ln().abs_diff(q64!(21.487562597)) <= Q64::EPS);
+    ///
+    /// // Close to one the result keeps the distance to one.
+    /// assert_eq!((Q64::ONE - Q64::DELTA).ln(), -Q64::DELTA);
+    /// assert_eq!((Q64::ONE + Q64::DELTA).ln(), Q64::DELTA);
+    /// assert!(q64!(0.999).ln().abs_diff(q64!(-0.0010005003)) <= q64!(0.000000001));
+    /// ```
+    /// 
+    /// # Panics
+    ///
+    /// ```rust,should_panic
+    /// use qmath::prelude::*;
+    /// let _ = Q64::ZERO.ln();
+    /// ```
+    fn ln(self) -> Self {
+        assert!(self > Self::ZERO, "[QBasic::ln] Expected a positive number.");
+
+        // self = m * 2^k, m in [sqrt(1/2), sqrt(2)), so that s stays small on both sides of 1.
+        // Worked with a 64-bit fraction, so that m is exact and s keeps its bits near 1.
+        let scale = |k: i32| if k >= 0 { I64F64::from_num(self) >> k } else { I64F64::from_num(self) << -k };
+        let mut k = self.int_log2();
+        if scale(k) >= I64F64::SQRT_2 {
+            k += 1;
+        }
+        let m = scale(k);
+
+        // ln(m) = 2 * atanh(s), s = (m - 1) / (m + 1) in (-0.172, 0.172).
+        let s = (m - I64F64::ONE) / (m + I64F64::ONE);
+        let s2 = s * s;
+        let mut term = s;
+        let mut sum = s;
+        for i in 1..=12 {
+            term *= s2;
+            sum += term / (2 * i + 1);
+        }
+
+        // Rounded to the nearest.
+        let rst = I64F64::LN_2 * i128::from(k) + sum * 2;
+        (rst + I64F64::from_bits(1 << 31)).to_num::<Self>()
     }
 
     fn half(self) -> Self {
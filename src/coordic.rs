@@ -92,9 +92,7 @@ impl QCoordic for Q64 {
             (x, y) = (xv, yv);
         }
         
-        let result = (y * result_factor, x * result_factor);
-        
-        result
+        (y * result_factor, x * result_factor)
     }
         
     fn sin(self) -> Self { self.sin_cos().0 }
@@ -118,7 +116,7 @@ impl QCoordic for Q64 {
     /// let _ = Q64::NEG_TWO.asin();
     /// ```
     fn asin(self) -> (Self, Self) {
-        assert!(self <= 1 && self >= -1, "[QCoordic::asin] Sine value should be in range of [-1, 1].");
+        assert!((-1..=1).contains(&self), "[QCoordic::asin] Sine value should be in range of [-1, 1].");
         
         let mut x = Self::ONE;
         let mut y = Self::ZERO;
@@ -139,13 +137,11 @@ impl QCoordic for Q64 {
             (x, y) = (xv * cos, yv * cos);
         }
         
-        let result ;
         if z > 0 {
-            result = (z, -z + Self::PI);
+            (z, -z + Self::PI)
         } else {
-            result = (-z - Self::PI, z);
+            (-z - Self::PI, z)
         }
-        result
     }
     
     /// # Panics
@@ -156,7 +152,7 @@ impl QCoordic for Q64 {
     /// let _ = Q64::NEG_TWO.acos();
     /// ```
     fn acos(self) -> (Self, Self) {
-        assert!(self <= 1 && self >= -1, "[QCoordic::acos] Can't acos value {}, cosine value should be in range of [-1, 1].", self);
+        assert!((-1..=1).contains(&self), "[QCoordic::acos] Can't acos value {}, cosine value should be in range of [-1, 1].", self);
         
         let mut x = Self::ZERO;
         let mut y = Self::ONE;
@@ -177,8 +173,7 @@ impl QCoordic for Q64 {
             (x, y) = (xv * cos, yv * cos);
         }
         
-        let result = (-z, z);
-        result
+        (-z, z)
     }
         
     fn atan(self) -> (Self, Self) {
@@ -202,13 +197,11 @@ impl QCoordic for Q64 {
             (x, y) = (xv * cos, yv * cos);
         }
     
-        let result;
         if z > 0 {
-            result = (z - Self::PI, z);
+            (z - Self::PI, z)
         } else {
-            result = (z, z + Self::PI);
+            (z, z + Self::PI)
         }
-        result
     }
 
     /// # Examples
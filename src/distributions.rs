@@ -0,0 +1,242 @@
+use crate::prelude::*;
+use crate::rng::QRng;
+
+/// Mean above which `poisson` and `binomial` switch from exact sampling to the normal approximation.
+const EXACT_MEAN_LIMIT: Q64 = Q64::lit("64");
+
+/// Number of trials up to which `binomial` counts Bernoulli trials one by one.
+const BERNOULLI_TRIALS_LIMIT: u32 = 64;
+
+/// Non-uniform distributions.
+///
+/// All samplers only use fixed-point arithmetic, so a given seed produces the same samples on
+/// every platform.
+impl QRng {
+    /// Generates a uniformly distributed random number in the range `(0, 1]`.
+    fn next_unit_nonzero(&mut self) -> Q64 {
+        Q64::ONE - self.next_unit()
+    }
+
+    /// Generates a uniformly distributed random number in the range `[min, max)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(7u32);
+    /// for _ in 0..1000 {
+    ///     let value = rng.uniform(q64!(-3), q64!(5));
+    ///     assert!(value >= -3 && value < 5);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `min` is greater than `max`.
+    pub fn uniform(&mut self, min: Q64, max: Q64) -> Q64 {
+        assert!(min <= max, "[QRng::uniform] Expected min <= max.");
+        min + (max - min) * self.next_unit()
+    }
+
+    /// Returns `true` with probability `p`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `p` is not in range of `[0, 1]`.
+    pub fn bernoulli(&mut self, p: Q64) -> bool {
+        assert!(p >= Q64::ZERO && p <= Q64::ONE, "[QRng::bernoulli] Expected p in range of [0, 1].");
+        self.next_unit() < p
+    }
+
+    /// Generates a normally distributed random number using the Box-Muller transform.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(42u32);
+    /// let samples: Vec<Q64> = (0..10000).map(|_| rng.normal(q64!(10), q64!(2))).collect();
+    /// let mean = samples.iter().copied().sum::<Q64>() / 10000;
+    /// let variance = samples.iter().map(|s| (*s - mean) * (*s - mean)).sum::<Q64>() / 10000;
+    /// assert!(mean.abs_diff(q64!(10)) < q64!(0.1));
+    /// assert!(variance.abs_diff(q64!(4)) < q64!(0.2));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `std` is negative.
+    ///
+    /// SAT
+    pub fn normal(&mut self, mean: Q64, std: Q64) -> Q64 {
+        assert!(std >= Q64::ZERO, "[QRng::normal] Expected std >= 0.");
+        let radius = (self.next_unit_nonzero().ln() * Q64::NEG_TWO).sqrt();
+        let angle = self.next_unit() * Q64::TAU;
+        mean.saturating_add(radius.saturating_mul(angle.cos()).saturating_mul(std))
+    }
+
+    /// Generates an exponentially distributed random number with rate `lambda`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(42u32);
+    /// let samples: Vec<Q64> = (0..10000).map(|_| rng.exponential(q64!(4))).collect();
+    /// let mean = samples.iter().copied().sum::<Q64>() / 10000;
+    /// let variance = samples.iter().map(|s| (*s - mean) * (*s - mean)).sum::<Q64>() / 10000;
+    /// assert!(samples.iter().all(|s| *s >= 0));
+    /// assert!(mean.abs_diff(q64!(0.25)) < q64!(0.01));
+    /// assert!(variance.abs_diff(q64!(0.0625)) < q64!(0.005));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `lambda` is not positive.
+    ///
+    /// SAT
+    pub fn exponential(&mut self, lambda: Q64) -> Q64 {
+        assert!(lambda > Q64::ZERO, "[QRng::exponential] Expected lambda > 0.");
+        (-self.next_unit_nonzero().ln()).saturating_div(lambda)
+    }
+
+    /// Generates a random number with the triangular distribution over `[min, max]` peaking at `mode`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(42u32);
+    /// let samples: Vec<Q64> = (0..10000).map(|_| rng.triangular(q64!(0), q64!(1), q64!(4))).collect();
+    /// let mean = samples.iter().copied().sum::<Q64>() / 10000;
+    /// assert!(samples.iter().all(|s| *s >= 0 && *s <= 4));
+    /// assert!(mean.abs_diff(q64!(5) / 3) < q64!(0.05));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `min <= mode <= max` does not hold.
+    pub fn triangular(&mut self, min: Q64, mode: Q64, max: Q64) -> Q64 {
+        assert!(min <= mode && mode <= max, "[QRng::triangular] Expected min <= mode <= max.");
+        let range = max - min;
+        if range == Q64::ZERO {
+            return min;
+        }
+
+        let split = (mode - min) / range;
+        let u = self.next_unit();
+        if u < split {
+            min + range * (u * split).sqrt()
+        } else {
+            max - range * ((Q64::ONE - u) * (Q64::ONE - split)).sqrt()
+        }
+    }
+
+    /// Generates a Poisson distributed random count with mean `lambda`.
+    ///
+    /// Counts unit-rate exponential arrivals while `lambda` is below 64, and falls back to the
+    /// rounded normal approximation above it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(42u32);
+    /// for lambda in [q64!(3.5), q64!(200)] {
+    ///     let samples: Vec<Q64> = (0..5000).map(|_| q64!(rng.poisson(lambda))).collect();
+    ///     let mean = samples.iter().copied().sum::<Q64>() / 5000;
+    ///     let variance = samples.iter().map(|s| (*s - mean) * (*s - mean)).sum::<Q64>() / 5000;
+    ///     assert!(mean.abs_diff(lambda) < lambda / 20);
+    ///     assert!(variance.abs_diff(lambda) < lambda / 10);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `lambda` is negative.
+    pub fn poisson(&mut self, lambda: Q64) -> u32 {
+        assert!(lambda >= Q64::ZERO, "[QRng::poisson] Expected lambda >= 0.");
+        if lambda > EXACT_MEAN_LIMIT {
+            let sample = self.normal(lambda, lambda.sqrt()).round();
+            return sample.max(Q64::ZERO).to_num();
+        }
+
+        let mut count = 0;
+        let mut arrival = -self.next_unit_nonzero().ln();
+        while arrival <= lambda {
+            count += 1;
+            arrival -= self.next_unit_nonzero().ln();
+        }
+        count
+    }
+
+    /// Generates a binomially distributed random count of successes in `n` trials with probability `p`.
+    ///
+    /// Counts trials one by one for small `n`, skips between successes with geometric gaps for
+    /// small means, and falls back to the rounded normal approximation when the mean of the rarer
+    /// outcome is above 64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(42u32);
+    /// for (n, p) in [(20u32, q64!(0.3)), (1000, q64!(0.02)), (1000, q64!(0.99)), (100000, q64!(0.5))] {
+    ///     let expected_mean = p * n as i64;
+    ///     let expected_variance = expected_mean * (Q64::ONE - p);
+    ///     let samples: Vec<Q64> = (0..2000).map(|_| q64!(rng.binomial(n, p))).collect();
+    ///     assert!(samples.iter().all(|s| *s <= n as i64));
+    ///     let mean = samples.iter().copied().sum::<Q64>() / 2000;
+    ///     let variance = samples.iter().map(|s| (*s - mean) * (*s - mean)).sum::<Q64>() / 2000;
+    ///     assert!(mean.abs_diff(expected_mean) < expected_mean / 20);
+    ///     assert!(variance.abs_diff(expected_variance) < expected_variance / 5);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `p` is not in range of `[0, 1]`.
+    pub fn binomial(&mut self, n: u32, p: Q64) -> u32 {
+        assert!(p >= Q64::ZERO && p <= Q64::ONE, "[QRng::binomial] Expected p in range of [0, 1].");
+        if p > Q64::HALF {
+            return n - self.binomial(n, Q64::ONE - p);
+        }
+        if p == Q64::ZERO {
+            return 0;
+        }
+        if n <= BERNOULLI_TRIALS_LIMIT {
+            return (0..n).filter(|_| self.next_unit() < p).count() as u32;
+        }
+
+        let mean = Q64::from_num(n).saturating_mul(p);
+        if mean > EXACT_MEAN_LIMIT {
+            let std = (mean * (Q64::ONE - p)).sqrt();
+            let sample = self.normal(mean, std).round();
+            return sample.clamp(Q64::ZERO, Q64::from_num(n)).to_num();
+        }
+
+        // Trials up to and including the next success follow a geometric distribution.
+        let ln_q = (Q64::ONE - p).ln();
+        let mut count = 0;
+        let mut trial = 0_u64;
+        loop {
+            let gap: u64 = self.next_unit_nonzero().ln().saturating_div(ln_q).floor().to_num();
+            trial += gap + 1;
+            if trial > n as u64 {
+                return count;
+            }
+            count += 1;
+        }
+    }
+}
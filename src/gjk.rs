@@ -0,0 +1,458 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::polygon::QPolygon;
+use crate::predicates::orient2d;
+use crate::ray::QSegment2;
+use crate::sat::QConvex;
+use crate::shapes::{QCapsule2, QCircle, QObb2};
+use crate::vec2::QVec2;
+use core::cmp::Ordering;
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on the GJK iterations. A polygon pair needs about as many as it has vertices, and
+/// curved shapes stop improving well before this in fixed point.
+const GJK_MAX_ITERATIONS: usize = 32;
+
+/// Upper bound on the edges EPA adds to the polytope.
+const EPA_MAX_ITERATIONS: usize = 32;
+
+/// EPA stops once a new support point gets less than this closer to the true boundary.
+const EPA_TOLERANCE: Q64 = Q64::lit("0.0001");
+
+/// Convex shapes described by their support function.
+pub trait QSupport {
+    /// Returns the point of the shape furthest along `dir`.
+    ///
+    /// `dir` need not be normalized. Ties may be broken arbitrarily but must be deterministic.
+    fn support(&self, dir: QVec2) -> QVec2;
+}
+
+/// The closest points of two separated shapes.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QDistance {
+    pub distance: Q64,
+    /// The point of the first shape closest to the second.
+    pub point_a: QVec2,
+    /// The point of the second shape closest to the first.
+    pub point_b: QVec2,
+}
+
+/// The penetration of two overlapping shapes.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QPenetration {
+    /// Unit normal pointing from the first shape to the second. Moving the second shape by
+    /// `normal * depth` separates them.
+    pub normal: QVec2,
+    pub depth: Q64,
+    /// The deepest point of the first shape inside the second.
+    pub point_a: QVec2,
+    /// The deepest point of the second shape inside the first.
+    pub point_b: QVec2,
+}
+
+/// A vertex of the Minkowski difference `A - B`, with the points of `A` and `B` it came from.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Vertex {
+    a: QVec2,
+    b: QVec2,
+    w: QVec2,
+}
+
+impl Vertex {
+    fn new<A: QSupport, B: QSupport>(a: &A, b: &B, dir: QVec2) -> Self {
+        let a = a.support(dir);
+        let b = b.support(-dir);
+        Self { a, b, w: a.saturating_sub(b) }
+    }
+}
+
+/// A GJK simplex with the barycentric weights of its closest point to the origin.
+struct Simplex {
+    vertices: [Vertex; 3],
+    weights: [Q64; 3],
+    count: usize,
+}
+
+impl Simplex {
+    /// Reduces the simplex to the feature closest to the origin and updates the weights.
+    ///
+    /// SAT
+    fn solve(&mut self) {
+        match self.count {
+            1 => self.weights[0] = Q64::ONE,
+            2 => self.solve_segment(0, 1),
+            _ => self.solve_triangle(),
+        }
+    }
+
+    /// Keeps the vertex at `index` only.
+    fn keep_vertex(&mut self, index: usize) {
+        self.vertices[0] = self.vertices[index];
+        self.weights[0] = Q64::ONE;
+        self.count = 1;
+    }
+
+    /// Keeps the edge between the vertices at `i` and `j`, given the unnormalized weights.
+    fn keep_edge(&mut self, i: usize, j: usize, wi: Q64, wj: Q64) {
+        let sum = wi.saturating_add(wj);
+        self.vertices = [self.vertices[i], self.vertices[j], self.vertices[j]];
+        self.weights = [wi.saturating_div(sum), wj.saturating_div(sum), Q64::ZERO];
+        self.count = 2;
+    }
+
+    /// SAT
+    fn solve_segment(&mut self, i: usize, j: usize) {
+        let (w1, w2) = (self.vertices[i].w, self.vertices[j].w);
+        let e12 = w2.saturating_sub(w1);
+        let d12_2 = -w1.dot(e12);
+        let d12_1 = w2.dot(e12);
+        if d12_2 <= Q64::ZERO {
+            self.keep_vertex(i);
+        } else if d12_1 <= Q64::ZERO {
+            self.keep_vertex(j);
+        } else {
+            self.keep_edge(i, j, d12_1, d12_2);
+        }
+    }
+
+    /// Voronoi region test of the origin against the triangle, as in Box2D.
+    ///
+    /// Only the signs of the triangle terms are needed, so they come from the exact [`orient2d`]
+    /// rather than from saturating products.
+    ///
+    /// SAT
+    fn solve_triangle(&mut self) {
+        let [w1, w2, w3] = self.vertices.map(|v| v.w);
+        let e12 = w2.saturating_sub(w1);
+        let (d12_1, d12_2) = (w2.dot(e12), -w1.dot(e12));
+        let e13 = w3.saturating_sub(w1);
+        let (d13_1, d13_2) = (w3.dot(e13), -w1.dot(e13));
+        let e23 = w3.saturating_sub(w2);
+        let (d23_1, d23_2) = (w3.dot(e23), -w2.dot(e23));
+
+        let n123 = orient2d(w1, w2, w3);
+        let inside = |p: QVec2, q: QVec2| n123 != Ordering::Equal && orient2d(QVec2::ZERO, p, q) == n123;
+        let d123_1 = inside(w2, w3);
+        let d123_2 = inside(w3, w1);
+        let d123_3 = inside(w1, w2);
+
+        if d12_2 <= Q64::ZERO && d13_2 <= Q64::ZERO {
+            self.keep_vertex(0);
+        } else if d12_1 > Q64::ZERO && d12_2 > Q64::ZERO && !d123_3 {
+            self.keep_edge(0, 1, d12_1, d12_2);
+        } else if d13_1 > Q64::ZERO && d13_2 > Q64::ZERO && !d123_2 {
+            self.keep_edge(0, 2, d13_1, d13_2);
+        } else if d12_1 <= Q64::ZERO && d23_2 <= Q64::ZERO {
+            self.keep_vertex(1);
+        } else if d13_1 <= Q64::ZERO && d23_1 <= Q64::ZERO {
+            self.keep_vertex(2);
+        } else if d23_1 > Q64::ZERO && d23_2 > Q64::ZERO && !d123_1 {
+            self.keep_edge(1, 2, d23_1, d23_2);
+        }
+        // Otherwise the origin is inside the triangle and all three vertices are kept.
+    }
+
+    /// Returns the weighted sum of `f` over the vertices.
+    ///
+    /// SAT
+    fn combine(&self, f: impl Fn(&Vertex) -> QVec2) -> QVec2 {
+        (0..self.count).fold(QVec2::ZERO, |acc, i| {
+            acc.saturating_add(f(&self.vertices[i]).saturating_mul_num(self.weights[i]))
+        })
+    }
+
+    /// Returns whether the origin lies exactly on the reduced simplex.
+    fn contains_origin(&self) -> bool {
+        match self.count {
+            1 => self.vertices[0].w == QVec2::ZERO,
+            2 => orient2d(self.vertices[0].w, self.vertices[1].w, QVec2::ZERO) == Ordering::Equal,
+            _ => true,
+        }
+    }
+
+    /// Returns the direction from the closest point towards the origin.
+    ///
+    /// SAT
+    fn search_direction(&self) -> QVec2 {
+        match self.count {
+            1 => -self.vertices[0].w,
+            _ => {
+                let (w1, w2) = (self.vertices[0].w, self.vertices[1].w);
+                let e12 = w2.saturating_sub(w1);
+                // The perpendicular of the edge on the side of the origin.
+                if orient2d(w1, w2, QVec2::ZERO) == Ordering::Greater { e12.perp() } else { -e12.perp() }
+            }
+        }
+    }
+}
+
+/// Runs GJK and returns the final simplex, which encloses the origin when the shapes overlap.
+///
+/// SAT
+fn gjk<A: QSupport, B: QSupport>(a: &A, b: &B) -> (Simplex, bool) {
+    let first = Vertex::new(a, b, QVec2::X);
+    let mut simplex = Simplex { vertices: [first; 3], weights: [Q64::ONE, Q64::ZERO, Q64::ZERO], count: 1 };
+
+    for _ in 0..GJK_MAX_ITERATIONS {
+        let previous = simplex.vertices;
+        let previous_count = simplex.count;
+        simplex.solve();
+        if simplex.count == 3 {
+            return (simplex, true);
+        }
+
+        let dir = simplex.search_direction();
+        if dir == QVec2::ZERO || simplex.contains_origin() {
+            // The origin lies on the simplex, so the shapes touch.
+            return (simplex, true);
+        }
+
+        let vertex = Vertex::new(a, b, dir);
+        if previous[..previous_count].contains(&vertex) {
+            // No progress, the simplex is already the closest feature.
+            break;
+        }
+        simplex.vertices[simplex.count] = vertex;
+        simplex.count += 1;
+    }
+
+    // Leaves the simplex solved whether the loop ended by convergence or by the bound.
+    simplex.solve();
+    let overlap = simplex.count == 3 || simplex.contains_origin();
+    (simplex, overlap)
+}
+
+/// Returns the distance and closest points of two convex shapes, or `None` if they overlap or
+/// touch.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::gjk::distance;
+/// use qmath::shapes::{QCapsule2, QCircle};
+/// use qmath::vec2::QVec2;
+///
+/// let capsule = QCapsule2::new(qvec2!(-2, 0), qvec2!(2, 0), q64!(0.5));
+/// let ball = QCircle::new(qvec2!(1, 2), q64!(1));
+/// let result = distance(&capsule, &ball).unwrap();
+/// assert!((result.distance - q64!(0.5)).abs() < q64!(0.001));
+/// assert!(result.point_a.abs_diff_eq(qvec2!(1, 0.5), q64!(0.001)));
+/// assert!(result.point_b.abs_diff_eq(qvec2!(1, 1), q64!(0.001)));
+///
+/// assert!(distance(&capsule, &QCircle::new(qvec2!(2.5, 1), q64!(1))).is_none());
+/// ```
+///
+/// SAT
+#[must_use]
+pub fn distance<A: QSupport, B: QSupport>(a: &A, b: &B) -> Option<QDistance> {
+    let (simplex, overlap) = gjk(a, b);
+    if overlap {
+        return None;
+    }
+    Some(QDistance {
+        distance: simplex.combine(|v| v.w).length(),
+        point_a: simplex.combine(|v| v.a),
+        point_b: simplex.combine(|v| v.b),
+    })
+}
+
+/// Returns whether two convex shapes overlap or touch.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::gjk::intersects;
+/// use qmath::shapes::QCircle;
+/// use qmath::vec2::QVec2;
+///
+/// let a = QCircle::new(qvec2!(0, 0), q64!(1));
+/// assert!(intersects(&a, &QCircle::new(qvec2!(1.5, 0), q64!(1))));
+/// assert!(!intersects(&a, &QCircle::new(qvec2!(2.5, 0), q64!(1))));
+/// ```
+#[must_use]
+pub fn intersects<A: QSupport, B: QSupport>(a: &A, b: &B) -> bool {
+    gjk(a, b).1
+}
+
+/// Returns the penetration of two convex shapes, or `None` if they are separated.
+///
+/// GJK finds a triangle of the Minkowski difference enclosing the origin, then EPA expands it
+/// towards the boundary closest to the origin. Touching shapes penetrate with a zero depth.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::aabb::QAabb2;
+/// use qmath::gjk::penetration;
+/// use qmath::shapes::QCapsule2;
+/// use qmath::vec2::QVec2;
+///
+/// let ground = QAabb2::new(qvec2!(-5, -1), qvec2!(5, 0));
+/// let capsule = QCapsule2::new(qvec2!(-1, 0.25), qvec2!(1, 0.25), q64!(0.5));
+/// let result = penetration(&ground, &capsule).unwrap();
+/// assert!(result.normal.abs_diff_eq(QVec2::Y, q64!(0.001)));
+/// assert!((result.depth - q64!(0.25)).abs() < q64!(0.001));
+/// assert!(result.point_a.y.abs() < q64!(0.001));
+/// assert!((result.point_b.y - q64!(-0.25)).abs() < q64!(0.001));
+///
+/// // Boxes are exact, and touching counts as a zero-depth penetration.
+/// let result = penetration(&ground, &QAabb2::new(qvec2!(0, -0.5), qvec2!(1, 1))).unwrap();
+/// assert_eq!((result.normal, result.depth), (QVec2::Y, q64!(0.5)));
+/// let resting = QAabb2::new(qvec2!(0, 0), qvec2!(1, 1));
+/// assert_eq!(penetration(&ground, &resting).unwrap().depth, q64!(0));
+///
+/// // Flat shapes have edges without a normal.
+/// let plank = QAabb2::new(qvec2!(-1, 0), qvec2!(1, 0));
+/// assert_eq!(penetration(&plank, &resting).unwrap().depth, q64!(0));
+///
+/// assert!(penetration(&ground, &QCapsule2::new(qvec2!(-1, 1), qvec2!(1, 1), q64!(0.5))).is_none());
+/// ```
+///
+/// SAT
+#[must_use]
+pub fn penetration<A: QSupport, B: QSupport>(a: &A, b: &B) -> Option<QPenetration> {
+    let (simplex, overlap) = gjk(a, b);
+    if !overlap {
+        return None;
+    }
+
+    let mut polytope: Vec<Vertex> = simplex.vertices[..simplex.count].to_vec();
+    if polytope.len() == 1 {
+        // The origin is a vertex, any other point of the difference makes an edge through it.
+        if let Some(vertex) = [QVec2::X, QVec2::Y, QVec2::NEG_X, QVec2::NEG_Y]
+            .into_iter()
+            .map(|dir| Vertex::new(a, b, dir))
+            .find(|v| v.w != polytope[0].w)
+        {
+            polytope.push(vertex);
+        }
+    }
+    if polytope.len() == 2 {
+        // The origin is on an edge, a point off its line makes a triangle with the origin on the
+        // boundary.
+        let normal = polytope[1].w.saturating_sub(polytope[0].w).perp();
+        let w0 = polytope[0].w;
+        if let Some(vertex) = [normal, -normal]
+            .into_iter()
+            .map(|dir| Vertex::new(a, b, dir))
+            .find(|v| v.w.saturating_sub(w0).dot(normal) != Q64::ZERO)
+        {
+            polytope.push(vertex);
+        }
+    }
+    if polytope.len() < 3 {
+        // The difference is flat, the shapes only touch.
+        let vertex = polytope[0];
+        let normal = polytope.get(1).and_then(|v| v.w.saturating_sub(vertex.w).perp().try_normalize()).unwrap_or(QVec2::X);
+        return Some(QPenetration { normal, depth: Q64::ZERO, point_a: vertex.a, point_b: vertex.b });
+    }
+    if polytope[1].w.saturating_sub(polytope[0].w).cross(polytope[2].w.saturating_sub(polytope[0].w)) < Q64::ZERO {
+        polytope.swap(1, 2);
+    }
+
+    let mut iteration = 0;
+    let (index, normal, depth) = loop {
+        let (index, normal, depth) = closest_edge(&polytope);
+        iteration += 1;
+        if iteration >= EPA_MAX_ITERATIONS {
+            break (index, normal, depth);
+        }
+        let vertex = Vertex::new(a, b, normal);
+        if vertex.w.dot(normal).saturating_sub(depth) <= EPA_TOLERANCE || polytope.contains(&vertex) {
+            break (index, normal, depth);
+        }
+        polytope.insert(index + 1, vertex);
+    };
+
+    // Projects the origin on the closest edge to find the witness points.
+    let v1 = polytope[index];
+    let v2 = polytope[(index + 1) % polytope.len()];
+    let edge = v2.w.saturating_sub(v1.w);
+    let t = (-v1.w.dot(edge)).saturating_div(edge.length_squared()).clamp(Q64::ZERO, Q64::ONE);
+    Some(QPenetration {
+        normal,
+        depth,
+        point_a: v1.a.lerp(v2.a, t),
+        point_b: v1.b.lerp(v2.b, t),
+    })
+}
+
+/// Returns the index, outward unit normal and distance of the polytope edge closest to the origin.
+///
+/// SAT
+fn closest_edge(polytope: &[Vertex]) -> (usize, QVec2, Q64) {
+    let n = polytope.len();
+    let mut best = (0, QVec2::X, Q64::MAX);
+    for i in 0..n {
+        let edge = polytope[(i + 1) % n].w.saturating_sub(polytope[i].w);
+        let Some(normal) = edge.perp().try_normalize() else {
+            continue;
+        };
+        let normal = -normal;
+        let distance = normal.dot(polytope[i].w);
+        if distance < best.2 {
+            best = (i, normal, distance);
+        }
+    }
+    best
+}
+
+impl QSupport for QVec2 {
+    fn support(&self, _dir: QVec2) -> QVec2 {
+        *self
+    }
+}
+
+impl QSupport for QSegment2 {
+    fn support(&self, dir: QVec2) -> QVec2 {
+        if self.a.dot(dir) >= self.b.dot(dir) { self.a } else { self.b }
+    }
+}
+
+impl QSupport for QCircle {
+    fn support(&self, dir: QVec2) -> QVec2 {
+        QCircle::support(self, dir)
+    }
+}
+
+impl QSupport for QCapsule2 {
+    fn support(&self, dir: QVec2) -> QVec2 {
+        QCapsule2::support(self, dir)
+    }
+}
+
+impl QSupport for QObb2 {
+    fn support(&self, dir: QVec2) -> QVec2 {
+        QObb2::support(self, dir)
+    }
+}
+
+impl QSupport for QAabb2 {
+    fn support(&self, dir: QVec2) -> QVec2 {
+        QVec2::new(
+            if dir.x >= Q64::ZERO { self.max.x } else { self.min.x },
+            if dir.y >= Q64::ZERO { self.max.y } else { self.min.y },
+        )
+    }
+}
+
+impl QSupport for QPolygon {
+    /// Returns the first vertex furthest along `dir`, assuming the polygon is convex.
+    fn support(&self, dir: QVec2) -> QVec2 {
+        self.vertices()[1..]
+            .iter()
+            .fold(self.vertices()[0], |best, v| if v.dot(dir) > best.dot(dir) { *v } else { best })
+    }
+}
+
+impl QSupport for QConvex {
+    fn support(&self, dir: QVec2) -> QVec2 {
+        match self {
+            QConvex::Circle(circle) => QSupport::support(circle, dir),
+            QConvex::Polygon(polygon) => QSupport::support(polygon, dir),
+        }
+    }
+}
@@ -0,0 +1,485 @@
+use crate::prelude::*;
+use crate::physics::{BAUMGARTE, QBody, QWorld};
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+
+/// Keeps two anchor points at a fixed distance, like a rod, or at most at that distance, like a
+/// rope.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QDistanceJoint {
+    pub body_a: usize,
+    pub body_b: usize,
+    /// Anchor on `body_a`, in body space.
+    pub local_anchor_a: QVec2,
+    /// Anchor on `body_b`, in body space.
+    pub local_anchor_b: QVec2,
+    pub length: Q64,
+    /// Whether the joint only resists stretching.
+    pub rope: bool,
+}
+
+/// Pins two bodies together at a point, letting them rotate freely, like a hinge.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QRevoluteJoint {
+    pub body_a: usize,
+    pub body_b: usize,
+    pub local_anchor_a: QVec2,
+    pub local_anchor_b: QVec2,
+}
+
+/// Lets `body_b` slide along an axis fixed to `body_a`, without rotating relative to it.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QPrismaticJoint {
+    pub body_a: usize,
+    pub body_b: usize,
+    pub local_anchor_a: QVec2,
+    pub local_anchor_b: QVec2,
+    /// Unit sliding axis, in the body space of `body_a`.
+    pub local_axis_a: QVec2,
+    /// Angle of `body_b` minus angle of `body_a` to keep.
+    pub reference_angle: Q64,
+}
+
+/// Glues two bodies together at a point and a relative angle.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QWeldJoint {
+    pub body_a: usize,
+    pub body_b: usize,
+    pub local_anchor_a: QVec2,
+    pub local_anchor_b: QVec2,
+    /// Angle of `body_b` minus angle of `body_a` to keep.
+    pub reference_angle: Q64,
+}
+
+/// A damped spring between two anchor points.
+///
+/// Solved as a soft constraint, which stays stable for any stiffness unlike an explicit spring
+/// force.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QSpringJoint {
+    pub body_a: usize,
+    pub body_b: usize,
+    pub local_anchor_a: QVec2,
+    pub local_anchor_b: QVec2,
+    pub rest_length: Q64,
+    /// Force per unit of stretch.
+    pub stiffness: Q64,
+    /// Force per unit of stretching speed.
+    pub damping: Q64,
+}
+
+/// A constraint between two bodies of a [`QWorld`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QJoint {
+    Distance(QDistanceJoint),
+    Revolute(QRevoluteJoint),
+    Prismatic(QPrismaticJoint),
+    Weld(QWeldJoint),
+    Spring(QSpringJoint),
+}
+
+impl QDistanceJoint {
+    /// Creates a rod between the world points `anchor_a` on `body_a` and `anchor_b` on `body_b`,
+    /// keeping their current distance.
+    ///
+    /// # Examples
+    ///
+    /// A rope lets the bob fall freely until it is taut:
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::joints::QDistanceJoint;
+    /// use qmath::physics::{QBody, QWorld};
+    /// use qmath::shapes::QCircle;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut world = QWorld::new(qvec2!(0, -10));
+    /// let ball = QCircle::new(QVec2::ZERO, q64!(0.25));
+    /// let ceiling = world.add_body(QBody::new_static(ball.into(), qvec2!(0, 10)));
+    /// let bob = world.add_body(QBody::new(ball.into(), qvec2!(0, 9), q64!(1)));
+    /// let slack = QDistanceJoint::new(&world, ceiling, bob, qvec2!(0, 10), qvec2!(0, 9));
+    /// world.add_joint(QDistanceJoint { rope: true, length: q64!(2), ..slack });
+    ///
+    /// for _ in 0..12 {
+    ///     world.step(q64!(1) / 60);
+    /// }
+    /// assert!(world.body(bob).position.y < q64!(8.8));
+    /// assert!(world.body(bob).velocity.y < q64!(-1.9));
+    ///
+    /// for _ in 0..300 {
+    ///     world.step(q64!(1) / 60);
+    /// }
+    /// let length = world.body(bob).position.distance(qvec2!(0, 10));
+    /// assert!((length - q64!(2)).abs() < q64!(0.01));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a body index is out of bounds.
+    #[must_use]
+    pub fn new(world: &QWorld, body_a: usize, body_b: usize, anchor_a: QVec2, anchor_b: QVec2) -> Self {
+        Self {
+            body_a,
+            body_b,
+            local_anchor_a: world.body(body_a).to_local(anchor_a),
+            local_anchor_b: world.body(body_b).to_local(anchor_b),
+            length: anchor_a.distance(anchor_b),
+            rope: false,
+        }
+    }
+}
+
+impl QRevoluteJoint {
+    /// Creates a hinge at the world point `anchor`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a body index is out of bounds.
+    #[must_use]
+    pub fn new(world: &QWorld, body_a: usize, body_b: usize, anchor: QVec2) -> Self {
+        Self {
+            body_a,
+            body_b,
+            local_anchor_a: world.body(body_a).to_local(anchor),
+            local_anchor_b: world.body(body_b).to_local(anchor),
+        }
+    }
+}
+
+impl QPrismaticJoint {
+    /// Creates a slider through the world point `anchor` along the world direction `axis`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::joints::QPrismaticJoint;
+    /// use qmath::physics::{QBody, QWorld};
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut world = QWorld::new(qvec2!(0, -10));
+    /// let square = QAabb2::new(qvec2!(-0.5, -0.5), qvec2!(0.5, 0.5));
+    /// let base = world.add_body(QBody::new_static(square.into(), qvec2!(0, 10)));
+    /// let slider = world.add_body(QBody::new(square.into(), qvec2!(-3, 7), q64!(1)));
+    /// world.add_joint(QPrismaticJoint::new(&world, base, slider, qvec2!(-3, 7), qvec2!(1, 1)));
+    ///
+    /// // Pushed sideways and off-center, it still only slides down the diagonal.
+    /// world.body_mut(slider).apply_impulse(qvec2!(3, 0), qvec2!(-3, 7.5));
+    /// for _ in 0..60 {
+    ///     world.step(q64!(1) / 60);
+    /// }
+    /// let body = world.body(slider);
+    /// assert!(body.position.y < q64!(6.5));
+    /// assert!((body.position.y - body.position.x - q64!(10)).abs() < q64!(0.001));
+    /// assert!(body.angle.abs() < q64!(0.001));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a body index is out of bounds or `axis` is zero.
+    #[must_use]
+    pub fn new(world: &QWorld, body_a: usize, body_b: usize, anchor: QVec2, axis: QVec2) -> Self {
+        assert!(axis != QVec2::ZERO, "[QPrismaticJoint::new] Expected a non-zero axis.");
+        let (a, b) = (world.body(body_a), world.body(body_b));
+        let rotation = a.rotation();
+        Self {
+            body_a,
+            body_b,
+            local_anchor_a: a.to_local(anchor),
+            local_anchor_b: b.to_local(anchor),
+            local_axis_a: QVec2::new(rotation.x, -rotation.y).rotate(axis.normalize()),
+            reference_angle: b.angle - a.angle,
+        }
+    }
+}
+
+impl QWeldJoint {
+    /// Welds the bodies at the world point `anchor`, keeping their current relative angle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::joints::QWeldJoint;
+    /// use qmath::physics::{QBody, QWorld};
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut world = QWorld::new(qvec2!(0, -10));
+    /// let square = QAabb2::new(qvec2!(-0.5, -0.5), qvec2!(0.5, 0.5));
+    /// let wall = world.add_body(QBody::new_static(square.into(), qvec2!(0, 10)));
+    /// let arm = world.add_body(QBody::new(square.into(), qvec2!(1, 10), q64!(1)));
+    /// world.body_mut(arm).angle = q64!(0.5);
+    /// world.add_joint(QWeldJoint::new(&world, wall, arm, qvec2!(0.5, 10)));
+    ///
+    /// // Gravity doesn't bend the arm down.
+    /// for _ in 0..120 {
+    ///     world.step(q64!(1) / 60);
+    /// }
+    /// let body = world.body(arm);
+    /// assert!((body.angle - q64!(0.5)).abs() < q64!(0.001));
+    /// assert!(body.position.distance(qvec2!(1, 10)) < q64!(0.001));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a body index is out of bounds.
+    #[must_use]
+    pub fn new(world: &QWorld, body_a: usize, body_b: usize, anchor: QVec2) -> Self {
+        let (a, b) = (world.body(body_a), world.body(body_b));
+        Self {
+            body_a,
+            body_b,
+            local_anchor_a: a.to_local(anchor),
+            local_anchor_b: b.to_local(anchor),
+            reference_angle: b.angle - a.angle,
+        }
+    }
+}
+
+impl QSpringJoint {
+    /// Creates a spring between the world points `anchor_a` on `body_a` and `anchor_b` on
+    /// `body_b`, at rest at their current distance.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a body index is out of bounds, or `stiffness` or `damping` is negative.
+    #[must_use]
+    pub fn new(world: &QWorld, body_a: usize, body_b: usize, anchor_a: QVec2, anchor_b: QVec2, stiffness: Q64, damping: Q64) -> Self {
+        assert!(stiffness >= Q64::ZERO, "[QSpringJoint::new] Expected stiffness >= 0.");
+        assert!(damping >= Q64::ZERO, "[QSpringJoint::new] Expected damping >= 0.");
+        Self {
+            body_a,
+            body_b,
+            local_anchor_a: world.body(body_a).to_local(anchor_a),
+            local_anchor_b: world.body(body_b).to_local(anchor_b),
+            rest_length: anchor_a.distance(anchor_b),
+            stiffness,
+            damping,
+        }
+    }
+}
+
+impl From<QDistanceJoint> for QJoint {
+    fn from(joint: QDistanceJoint) -> Self {
+        Self::Distance(joint)
+    }
+}
+
+impl From<QRevoluteJoint> for QJoint {
+    fn from(joint: QRevoluteJoint) -> Self {
+        Self::Revolute(joint)
+    }
+}
+
+impl From<QPrismaticJoint> for QJoint {
+    fn from(joint: QPrismaticJoint) -> Self {
+        Self::Prismatic(joint)
+    }
+}
+
+impl From<QWeldJoint> for QJoint {
+    fn from(joint: QWeldJoint) -> Self {
+        Self::Weld(joint)
+    }
+}
+
+impl From<QSpringJoint> for QJoint {
+    fn from(joint: QSpringJoint) -> Self {
+        Self::Spring(joint)
+    }
+}
+
+/// One scalar constraint `C(x) = 0` with its velocity Jacobian.
+///
+/// The constraint velocity is `dot(linear, v_b - v_a) + angular_b * w_b - angular_a * w_a`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Row {
+    a: usize,
+    b: usize,
+    linear: QVec2,
+    angular_a: Q64,
+    angular_b: Q64,
+    mass: Q64,
+    bias: Q64,
+    /// Softness of the constraint, zero for a rigid one.
+    gamma: Q64,
+    impulse: Q64,
+    lower: Q64,
+    upper: Q64,
+}
+
+impl QJoint {
+    /// Returns the bodies joined.
+    #[inline]
+    #[must_use]
+    pub fn bodies(&self) -> (usize, usize) {
+        match self {
+            QJoint::Distance(j) => (j.body_a, j.body_b),
+            QJoint::Revolute(j) => (j.body_a, j.body_b),
+            QJoint::Prismatic(j) => (j.body_a, j.body_b),
+            QJoint::Weld(j) => (j.body_a, j.body_b),
+            QJoint::Spring(j) => (j.body_a, j.body_b),
+        }
+    }
+
+    /// Builds the rows of the joint for a step from the current positions.
+    ///
+    /// SAT
+    pub(crate) fn rows(&self, bodies: &[QBody], dt: Q64) -> Vec<Row> {
+        let (a, b) = self.bodies();
+        let builder = RowBuilder { a, b, body_a: &bodies[a], body_b: &bodies[b], dt };
+        match self {
+            QJoint::Distance(j) => {
+                let (r_a, r_b, delta) = builder.anchors(j.local_anchor_a, j.local_anchor_b);
+                let length = delta.length();
+                let axis = if length == Q64::ZERO { QVec2::X } else { delta.saturating_div_num(length) };
+                let error = length.saturating_sub(j.length);
+                let mut row = builder.point_row(r_a, r_b, axis, error);
+                if j.rope {
+                    // A slack rope lets the anchors close the gap within the step, no faster.
+                    if error < Q64::ZERO {
+                        row.bias = error.saturating_div(dt);
+                    }
+                    row.upper = Q64::ZERO;
+                }
+                vec![row]
+            }
+            QJoint::Revolute(j) => {
+                let (r_a, r_b, delta) = builder.anchors(j.local_anchor_a, j.local_anchor_b);
+                vec![builder.point_row(r_a, r_b, QVec2::X, delta.x), builder.point_row(r_a, r_b, QVec2::Y, delta.y)]
+            }
+            QJoint::Prismatic(j) => {
+                let (r_a, r_b, delta) = builder.anchors(j.local_anchor_a, j.local_anchor_b);
+                let normal = builder.body_a.rotation().rotate(j.local_axis_a).perp();
+                let mut row = builder.point_row(r_a, r_b, normal, delta.dot(normal));
+                // The axis is attached to `body_a`, so it turns around its center.
+                row.angular_a = r_a.saturating_add(delta).cross(normal);
+                row.mass = builder.mass(row.linear, row.angular_a, row.angular_b, Q64::ZERO);
+                vec![row, builder.angle_row(j.reference_angle)]
+            }
+            QJoint::Weld(j) => {
+                let (r_a, r_b, delta) = builder.anchors(j.local_anchor_a, j.local_anchor_b);
+                vec![
+                    builder.point_row(r_a, r_b, QVec2::X, delta.x),
+                    builder.point_row(r_a, r_b, QVec2::Y, delta.y),
+                    builder.angle_row(j.reference_angle),
+                ]
+            }
+            QJoint::Spring(j) => {
+                let (r_a, r_b, delta) = builder.anchors(j.local_anchor_a, j.local_anchor_b);
+                let length = delta.length();
+                let axis = if length == Q64::ZERO { QVec2::X } else { delta.saturating_div_num(length) };
+                let error = length.saturating_sub(j.rest_length);
+
+                // Soft constraint of Box2D: `gamma = 1 / (dt * (c + dt * k))`, `bias = C * dt * k * gamma`.
+                let softness = dt.saturating_mul(j.damping.saturating_add(dt.saturating_mul(j.stiffness)));
+                if softness == Q64::ZERO {
+                    return Vec::new();
+                }
+                let gamma = softness.saturating_recip();
+                let mut row = builder.point_row(r_a, r_b, axis, Q64::ZERO);
+                row.gamma = gamma;
+                row.bias = error.saturating_mul(dt).saturating_mul(j.stiffness).saturating_mul(gamma);
+                row.mass = builder.mass(row.linear, row.angular_a, row.angular_b, gamma);
+                vec![row]
+            }
+        }
+    }
+}
+
+/// Shared state to build the rows of a joint.
+struct RowBuilder<'a> {
+    a: usize,
+    b: usize,
+    body_a: &'a QBody,
+    body_b: &'a QBody,
+    dt: Q64,
+}
+
+impl RowBuilder<'_> {
+    /// Returns the world offsets of the anchors from the body centers, and the vector between the
+    /// anchors.
+    ///
+    /// SAT
+    fn anchors(&self, local_a: QVec2, local_b: QVec2) -> (QVec2, QVec2, QVec2) {
+        let r_a = self.body_a.rotation().rotate(local_a);
+        let r_b = self.body_b.rotation().rotate(local_b);
+        let p_a = self.body_a.position.saturating_add(r_a);
+        let p_b = self.body_b.position.saturating_add(r_b);
+        (r_a, r_b, p_b.saturating_sub(p_a))
+    }
+
+    /// SAT
+    fn mass(&self, linear: QVec2, angular_a: Q64, angular_b: Q64, gamma: Q64) -> Q64 {
+        let k = self
+            .body_a
+            .inv_mass
+            .saturating_add(self.body_b.inv_mass)
+            .saturating_mul(linear.length_squared())
+            .saturating_add(self.body_a.inv_inertia.saturating_mul(angular_a.saturating_mul(angular_a)))
+            .saturating_add(self.body_b.inv_inertia.saturating_mul(angular_b.saturating_mul(angular_b)))
+            .saturating_add(gamma);
+        if k > Q64::ZERO { k.saturating_recip() } else { Q64::ZERO }
+    }
+
+    fn row(&self, linear: QVec2, angular_a: Q64, angular_b: Q64, error: Q64) -> Row {
+        Row {
+            a: self.a,
+            b: self.b,
+            linear,
+            angular_a,
+            angular_b,
+            mass: self.mass(linear, angular_a, angular_b, Q64::ZERO),
+            bias: BAUMGARTE.saturating_mul(error).saturating_div(self.dt),
+            gamma: Q64::ZERO,
+            impulse: Q64::ZERO,
+            lower: Q64::MIN,
+            upper: Q64::MAX,
+        }
+    }
+
+    /// Keeps the anchors from separating along the unit `axis`.
+    fn point_row(&self, r_a: QVec2, r_b: QVec2, axis: QVec2, error: Q64) -> Row {
+        self.row(axis, r_a.cross(axis), r_b.cross(axis), error)
+    }
+
+    /// Keeps the angle of `body_b` minus the angle of `body_a` at `reference`.
+    ///
+    /// SAT
+    fn angle_row(&self, reference: Q64) -> Row {
+        let error = self.body_b.angle.saturating_sub(self.body_a.angle).saturating_sub(reference);
+        self.row(QVec2::ZERO, Q64::ONE, Q64::ONE, error)
+    }
+}
+
+/// Applies one iteration of sequential impulses to a row.
+///
+/// SAT
+pub(crate) fn solve_row(row: &mut Row, bodies: &mut [QBody]) {
+    let (body_a, body_b) = (&bodies[row.a], &bodies[row.b]);
+    let velocity = row
+        .linear
+        .dot(body_b.velocity.saturating_sub(body_a.velocity))
+        .saturating_add(row.angular_b.saturating_mul(body_b.angular_velocity))
+        .saturating_sub(row.angular_a.saturating_mul(body_a.angular_velocity));
+
+    let impulse = -row
+        .mass
+        .saturating_mul(velocity.saturating_add(row.bias).saturating_add(row.gamma.saturating_mul(row.impulse)));
+    let total = row.impulse.saturating_add(impulse).clamp(row.lower, row.upper);
+    let impulse = total - row.impulse;
+    row.impulse = total;
+
+    let body_a = &mut bodies[row.a];
+    body_a.velocity = body_a.velocity.saturating_sub(row.linear.saturating_mul_num(impulse.saturating_mul(body_a.inv_mass)));
+    body_a.angular_velocity = body_a
+        .angular_velocity
+        .saturating_sub(body_a.inv_inertia.saturating_mul(row.angular_a).saturating_mul(impulse));
+    let body_b = &mut bodies[row.b];
+    body_b.velocity = body_b.velocity.saturating_add(row.linear.saturating_mul_num(impulse.saturating_mul(body_b.inv_mass)));
+    body_b.angular_velocity = body_b
+        .angular_velocity
+        .saturating_add(body_b.inv_inertia.saturating_mul(row.angular_b).saturating_mul(impulse));
+}
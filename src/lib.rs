@@ -1,10 +1,34 @@
 
 mod basic;
 mod coordic;
+mod wide;
 
 pub mod vec2;
 pub mod rng;
 pub mod dir;
+pub mod distributions;
+pub mod sampling;
+pub mod seq;
+pub mod noise;
+pub mod poisson_disk;
+pub mod aabb;
+pub mod shapes;
+pub mod ray;
+pub mod polygon;
+pub mod predicates;
+pub mod sat;
+pub mod gjk;
+pub mod sweep;
+pub mod spatial_hash;
+pub mod aabb_tree;
+pub mod quadtree;
+pub mod physics;
+pub mod joints;
+pub mod verlet;
+pub mod smooth;
+pub mod steering;
+pub mod pathfinding;
+pub mod navmesh;
 pub mod prelude {
     pub use crate::{
         Q64,
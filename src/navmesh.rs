@@ -0,0 +1,487 @@
+use crate::prelude::*;
+use crate::polygon::QPolygon;
+use crate::predicates::{incircle, orient2d};
+use crate::vec2::QVec2;
+use core::cmp::{Ordering, Reverse};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+
+/// A navigation mesh: the walkable area of a map as triangles, for pathfinding off the grid.
+///
+/// The triangles are a constrained Delaunay triangulation of a boundary polygon and its holes,
+/// built with the exact predicates, so the mesh and every path found on it are the same on every
+/// machine.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QNavMesh {
+    vertices: Vec<QVec2>,
+    /// Vertex indices of each triangle, counter-clockwise.
+    triangles: Vec<[usize; 3]>,
+    /// Triangle across the edge from vertex `i` to vertex `i + 1`, if walkable.
+    neighbors: Vec<[Option<usize>; 3]>,
+}
+
+/// Returns `[a, b, c]` or `[a, c, b]`, whichever is counter-clockwise.
+fn ccw(points: &[QVec2], a: usize, b: usize, c: usize) -> [usize; 3] {
+    if orient2d(points[a], points[b], points[c]) == Ordering::Greater { [a, b, c] } else { [a, c, b] }
+}
+
+/// Returns true if the segments `ab` and `cd` cross at a point interior to both.
+fn crosses(a: QVec2, b: QVec2, c: QVec2, d: QVec2) -> bool {
+    let opposite = |x: Ordering, y: Ordering| x != Ordering::Equal && y != Ordering::Equal && x != y;
+    opposite(orient2d(a, b, c), orient2d(a, b, d)) && opposite(orient2d(c, d, a), orient2d(c, d, b))
+}
+
+/// Returns true if `p` is on the closed segment `ab`.
+fn on_segment(p: QVec2, a: QVec2, b: QVec2) -> bool {
+    orient2d(a, b, p) == Ordering::Equal
+        && (a.x.min(b.x)..=a.x.max(b.x)).contains(&p.x)
+        && (a.y.min(b.y)..=a.y.max(b.y)).contains(&p.y)
+}
+
+/// Returns the undirected key of an edge.
+fn key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// Incremental constrained Delaunay triangulation of a point set within a super triangle.
+struct Triangulation {
+    points: Vec<QVec2>,
+    triangles: Vec<[usize; 3]>,
+    constraints: BTreeSet<(usize, usize)>,
+}
+
+impl Triangulation {
+    /// Inserts a point with Bowyer-Watson: the triangles whose circumcircle holds it are replaced
+    /// by a fan around it.
+    fn insert_point(&mut self, p: usize) {
+        let point = self.points[p];
+        let (bad, good): (Vec<[usize; 3]>, Vec<_>) = core::mem::take(&mut self.triangles)
+            .into_iter()
+            .partition(|t| incircle(self.points[t[0]], self.points[t[1]], self.points[t[2]], point) == Ordering::Greater);
+        let edges: BTreeSet<_> = bad.iter().flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])]).collect();
+        let mut triangles = good;
+        for &(u, v) in &edges {
+            if !edges.contains(&(v, u)) {
+                triangles.push([u, v, p]);
+            }
+        }
+        self.triangles = triangles;
+    }
+
+    /// Forces the edge from `a` to `b` into the triangulation, splitting it at vertices it passes
+    /// through, and records it as a constraint.
+    fn insert_constraint(&mut self, a: usize, b: usize) {
+        let (pa, pb) = (self.points[a], self.points[b]);
+        let between = |p: QVec2| {
+            let (lo, hi, x) = if pa.x != pb.x { (pa.x.min(pb.x), pa.x.max(pb.x), p.x) } else { (pa.y.min(pb.y), pa.y.max(pb.y), p.y) };
+            lo < x && x < hi
+        };
+        let through = (0..self.points.len()).find(|&v| {
+            let p = self.points[v];
+            v != a && v != b && orient2d(pa, pb, p) == Ordering::Equal && between(p)
+        });
+        if let Some(v) = through {
+            self.insert_constraint(a, v);
+            self.insert_constraint(v, b);
+            return;
+        }
+        self.constraints.insert(key(a, b));
+
+        let edge = |t: &[usize; 3], i: usize| (t[i], t[(i + 1) % 3]);
+        let (crossed, kept): (Vec<[usize; 3]>, Vec<_>) = core::mem::take(&mut self.triangles).into_iter().partition(|t| {
+            (0..3).any(|i| {
+                let (u, v) = edge(t, i);
+                crosses(pa, pb, self.points[u], self.points[v])
+            })
+        });
+        if crossed.is_empty() {
+            self.triangles = kept;
+            return;
+        }
+        // The crossed triangles leave a cavity, walked counter-clockwise: right of ab from a to b,
+        // then left of it back to a.
+        let edges: BTreeSet<_> = crossed.iter().flat_map(|t| (0..3).map(move |i| edge(t, i))).collect();
+        let mut next = BTreeMap::new();
+        for &(u, v) in &edges {
+            if !edges.contains(&(v, u)) {
+                next.insert(u, v);
+            } else {
+                assert!(!self.constraints.contains(&key(u, v)), "[QNavMesh::new] Expected non-crossing edges.");
+            }
+        }
+        let chain = |from: usize, to: usize| {
+            let mut vertices = Vec::new();
+            let mut v = next[&from];
+            while v != to {
+                vertices.push(v);
+                v = next[&v];
+            }
+            vertices
+        };
+        let (right, left) = (chain(a, b), chain(b, a));
+        self.triangles = kept;
+        self.fill(a, b, &right);
+        self.fill(b, a, &left);
+    }
+
+    /// Triangulates the pseudo-polygon made of the edge `ab` and the `chain` of vertices on one
+    /// side of it, picking at each level the vertex whose circle through `a` and `b` is empty.
+    fn fill(&mut self, a: usize, b: usize, chain: &[usize]) {
+        if chain.is_empty() {
+            return;
+        }
+        let mut c = 0;
+        for d in 1..chain.len() {
+            let [x, y, z] = ccw(&self.points, a, b, chain[c]);
+            if incircle(self.points[x], self.points[y], self.points[z], self.points[chain[d]]) == Ordering::Greater {
+                c = d;
+            }
+        }
+        self.fill(a, chain[c], &chain[..c]);
+        self.fill(chain[c], b, &chain[c + 1..]);
+        self.triangles.push(ccw(&self.points, a, b, chain[c]));
+    }
+}
+
+/// Returns the neighbors of each triangle, across edges shared with another triangle.
+fn neighbors(triangles: &[[usize; 3]]) -> Vec<[Option<usize>; 3]> {
+    let mut owners = BTreeMap::new();
+    for (index, t) in triangles.iter().enumerate() {
+        for i in 0..3 {
+            owners.insert((t[i], t[(i + 1) % 3]), index);
+        }
+    }
+    triangles.iter().map(|t| core::array::from_fn(|i| owners.get(&(t[(i + 1) % 3], t[i])).copied())).collect()
+}
+
+impl QNavMesh {
+    /// Triangulates the area inside `boundary` and outside every hole.
+    ///
+    /// Either winding works. Polygon edges must not cross or overlap each other, though polygons
+    /// may touch at vertices. Coordinates should stay well inside the range of `Q64`, as the
+    /// triangulation starts from a triangle around them about twenty times larger.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::navmesh::QNavMesh;
+    /// use qmath::polygon::QPolygon;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let room = QPolygon::new(vec![qvec2!(0, 0), qvec2!(10, 0), qvec2!(10, 10), qvec2!(0, 10)]);
+    /// let pillar = QPolygon::new(vec![qvec2!(4, 3), qvec2!(6, 3), qvec2!(6, 9), qvec2!(4, 9)]);
+    /// let mesh = QNavMesh::new(&room, &[pillar]);
+    ///
+    /// // Eight corners and one hole give eight triangles covering the floor.
+    /// assert_eq!(mesh.triangles().len(), 8);
+    /// let area: Q64 = (0..8).map(|i| QPolygon::new(mesh.triangle(i).to_vec()).area()).sum();
+    /// assert_eq!(area, q64!(88));
+    /// assert_eq!(mesh.locate(qvec2!(5, 5)), None);
+    /// assert!(mesh.locate(qvec2!(5, 1)).is_some());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if polygon edges cross, a hole isn't inside the boundary or the coordinates are
+    /// too large.
+    ///
+    /// ```rust,should_panic
+    /// use qmath::prelude::*;
+    /// use qmath::navmesh::QNavMesh;
+    /// use qmath::polygon::QPolygon;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let room = QPolygon::new(vec![qvec2!(0, 0), qvec2!(10, 0), qvec2!(10, 10), qvec2!(0, 10)]);
+    /// let outside = QPolygon::new(vec![qvec2!(8, 8), qvec2!(12, 8), qvec2!(12, 12)]);
+    /// let _ = QNavMesh::new(&room, &[outside]);
+    /// ```
+    #[must_use]
+    pub fn new(boundary: &QPolygon, holes: &[QPolygon]) -> Self {
+        // The vertices and edge midpoints, as holes may touch the boundary at vertices.
+        assert!(
+            holes.iter().flat_map(|hole| hole.edges()).all(|edge| {
+                boundary.contains_point(edge.a) && boundary.contains_point(edge.a.midpoint(edge.b))
+            }),
+            "[QNavMesh::new] Expected holes inside the boundary."
+        );
+        let polygons: Vec<&QPolygon> = core::iter::once(boundary).chain(holes).collect();
+        let mut points = Vec::new();
+        let mut lookup = BTreeMap::new();
+        let rings: Vec<Vec<usize>> = polygons
+            .iter()
+            .map(|polygon| {
+                polygon
+                    .vertices()
+                    .iter()
+                    .map(|&v| {
+                        *lookup.entry((v.x, v.y)).or_insert_with(|| {
+                            points.push(v);
+                            points.len() - 1
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // A super triangle around everything, left out of the mesh in the end.
+        let count = points.len();
+        let aabb = boundary.aabb();
+        let center = aabb.center();
+        let size = aabb.size().max_element().max(Q64::ONE).saturating_mul(Q64::from_num(20));
+        points.push(QVec2::new(center.x.saturating_sub(size), center.y.saturating_sub(size)));
+        points.push(QVec2::new(center.x.saturating_add(size), center.y.saturating_sub(size)));
+        points.push(QVec2::new(center.x, center.y.saturating_add(size)));
+        let [s0, s1, s2] = [count, count + 1, count + 2];
+        assert!(
+            points[..count].iter().all(|&p| {
+                orient2d(points[s0], points[s1], p) == Ordering::Greater
+                    && orient2d(points[s1], points[s2], p) == Ordering::Greater
+                    && orient2d(points[s2], points[s0], p) == Ordering::Greater
+            }),
+            "[QNavMesh::new] Coordinates too large."
+        );
+
+        let mut triangulation = Triangulation { points, triangles: vec![[s0, s1, s2]], constraints: BTreeSet::new() };
+        for p in 0..count {
+            triangulation.insert_point(p);
+        }
+        for ring in &rings {
+            for (i, &a) in ring.iter().enumerate() {
+                triangulation.insert_constraint(a, ring[(i + 1) % ring.len()]);
+            }
+        }
+
+        // Walkable triangles are an odd number of constraint crossings away from the outside.
+        let Triangulation { mut points, triangles, constraints } = triangulation;
+        let adjacent = neighbors(&triangles);
+        let mut depths = vec![usize::MAX; triangles.len()];
+        let mut queue = VecDeque::new();
+        for (index, t) in triangles.iter().enumerate() {
+            if t.iter().any(|&v| v >= count) {
+                depths[index] = 0;
+                queue.push_back(index);
+            }
+        }
+        while let Some(index) = queue.pop_front() {
+            let t = triangles[index];
+            for i in 0..3 {
+                let Some(next) = adjacent[index][i] else { continue };
+                let crossing = constraints.contains(&key(t[i], t[(i + 1) % 3]));
+                let depth = depths[index] + usize::from(crossing);
+                if depth < depths[next] {
+                    depths[next] = depth;
+                    if crossing {
+                        queue.push_back(next);
+                    } else {
+                        queue.push_front(next);
+                    }
+                }
+            }
+        }
+
+        let triangles: Vec<_> = triangles.into_iter().zip(depths).filter(|&(_, depth)| depth % 2 == 1).map(|(t, _)| t).collect();
+        points.truncate(count);
+        Self { vertices: points, neighbors: neighbors(&triangles), triangles }
+    }
+
+    /// Returns the vertices of the mesh.
+    #[inline]
+    #[must_use]
+    pub fn vertices(&self) -> &[QVec2] {
+        &self.vertices
+    }
+
+    /// Returns the vertex indices of each triangle, counter-clockwise.
+    #[inline]
+    #[must_use]
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// Returns the corners of a triangle, counter-clockwise.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn triangle(&self, index: usize) -> [QVec2; 3] {
+        self.triangles[index].map(|v| self.vertices[v])
+    }
+
+    /// Returns the triangles across each edge of a triangle, from vertex `i` to vertex `i + 1`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn neighbors(&self, index: usize) -> [Option<usize>; 3] {
+        self.neighbors[index]
+    }
+
+    /// Returns the first triangle containing `point`, or `None` if it is outside the walkable
+    /// area. Points on edges belong to the triangle with the lowest index.
+    #[must_use]
+    pub fn locate(&self, point: QVec2) -> Option<usize> {
+        (0..self.triangles.len()).find(|&index| {
+            let [a, b, c] = self.triangle(index);
+            [(a, b), (b, c), (c, a)].iter().all(|&(u, v)| orient2d(u, v, point) != Ordering::Less)
+        })
+    }
+
+    /// Finds a short path from `start` to `goal` through the walkable area, as waypoints from
+    /// `start` to `goal` that turn only at mesh vertices. Returns `None` if an end is outside the
+    /// walkable area or the two aren't connected.
+    ///
+    /// The triangles are searched with A*, from edge midpoint to edge midpoint with ties broken by
+    /// the lowest triangle, and the corridor is straightened with the simple stupid funnel
+    /// algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::navmesh::QNavMesh;
+    /// use qmath::polygon::QPolygon;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let room = QPolygon::new(vec![qvec2!(0, 0), qvec2!(10, 0), qvec2!(10, 10), qvec2!(0, 10)]);
+    /// let pillar = QPolygon::new(vec![qvec2!(4, 3), qvec2!(6, 3), qvec2!(6, 9), qvec2!(4, 9)]);
+    /// let mesh = QNavMesh::new(&room, &[pillar]);
+    ///
+    /// // Around the short side of the pillar.
+    /// let path = mesh.find_path(qvec2!(2, 5), qvec2!(8, 5)).unwrap();
+    /// assert_eq!(path, vec![qvec2!(2, 5), qvec2!(4, 3), qvec2!(6, 3), qvec2!(8, 5)]);
+    ///
+    /// // In plain sight.
+    /// assert_eq!(mesh.find_path(qvec2!(1, 1), qvec2!(9, 2)).unwrap(), vec![qvec2!(1, 1), qvec2!(9, 2)]);
+    /// assert_eq!(mesh.find_path(qvec2!(1, 1), qvec2!(5, 5)), None);
+    ///
+    /// // Around the inner corner of an L.
+    /// let l = QPolygon::new(vec![qvec2!(0, 0), qvec2!(4, 0), qvec2!(4, 1), qvec2!(1, 1), qvec2!(1, 4), qvec2!(0, 4)]);
+    /// let mesh = QNavMesh::new(&l, &[]);
+    /// let path = mesh.find_path(qvec2!(3.5, 0.5), qvec2!(0.5, 3.5)).unwrap();
+    /// assert_eq!(path, vec![qvec2!(3.5, 0.5), qvec2!(1, 1), qvec2!(0.5, 3.5)]);
+    ///
+    /// // From a point on an edge between two triangles.
+    /// let room = QPolygon::new(vec![qvec2!(0, 0), qvec2!(5, 0), qvec2!(10, 0), qvec2!(10, 10), qvec2!(0, 10)]);
+    /// let notch = QPolygon::new(vec![qvec2!(5, 0), qvec2!(7, 3), qvec2!(3, 3)]);
+    /// let mesh = QNavMesh::new(&room, &[notch]);
+    /// let path = mesh.find_path(qvec2!(1, 1), qvec2!(9, 1)).unwrap();
+    /// assert_eq!(path, vec![qvec2!(1, 1), qvec2!(3, 3), qvec2!(7, 3), qvec2!(9, 1)]);
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn find_path(&self, start: QVec2, goal: QVec2) -> Option<Vec<QVec2>> {
+        let corridor = self.corridor(self.locate(start)?, self.locate(goal)?, start, goal)?;
+        let mut portals = vec![(start, start)];
+        for pair in corridor.windows(2) {
+            let t = self.triangles[pair[0]];
+            let i = (0..3).find(|&i| self.neighbors[pair[0]][i] == Some(pair[1])).unwrap();
+            // Leaving through the edge from u to v, v is on the left.
+            portals.push((self.vertices[t[(i + 1) % 3]], self.vertices[t[i]]));
+        }
+        portals.push((goal, goal));
+        Some(funnel(&portals))
+    }
+
+    /// Returns the triangles from `from` to `to` found by A*.
+    ///
+    /// SAT
+    fn corridor(&self, from: usize, to: usize, start: QVec2, goal: QVec2) -> Option<Vec<usize>> {
+        let mut costs = vec![Q64::MAX; self.triangles.len()];
+        let mut entries = vec![start; self.triangles.len()];
+        let mut parents = vec![usize::MAX; self.triangles.len()];
+        let mut closed = vec![false; self.triangles.len()];
+        let mut open = BinaryHeap::new();
+        costs[from] = Q64::ZERO;
+        let h = start.distance(goal);
+        open.push(Reverse((h, h, from)));
+
+        while let Some(Reverse((_, _, index))) = open.pop() {
+            if closed[index] {
+                continue;
+            }
+            if index == to {
+                let mut corridor = vec![index];
+                while parents[corridor[corridor.len() - 1]] != usize::MAX {
+                    corridor.push(parents[corridor[corridor.len() - 1]]);
+                }
+                corridor.reverse();
+                return Some(corridor);
+            }
+            closed[index] = true;
+            let t = self.triangles[index];
+            for i in 0..3 {
+                let Some(next) = self.neighbors[index][i] else { continue };
+                let midpoint = self.vertices[t[i]].midpoint(self.vertices[t[(i + 1) % 3]]);
+                let cost = costs[index].saturating_add(entries[index].distance(midpoint));
+                if closed[next] || cost >= costs[next] {
+                    continue;
+                }
+                costs[next] = cost;
+                entries[next] = midpoint;
+                parents[next] = index;
+                let h = midpoint.distance(goal);
+                open.push(Reverse((cost.saturating_add(h), h, next)));
+            }
+        }
+        None
+    }
+}
+
+/// Straightens a corridor given by its `(left, right)` portals, the first and last being the
+/// start and the goal, with the simple stupid funnel algorithm.
+fn funnel(portals: &[(QVec2, QVec2)]) -> Vec<QVec2> {
+    let (start, goal) = (portals[0].0, portals[portals.len() - 1].0);
+    let mut points = vec![start];
+    let (mut apex, mut left, mut right) = (start, start, start);
+    let (mut left_index, mut right_index) = (0, 0);
+    let mut i = 1;
+    while i < portals.len() {
+        let (portal_left, portal_right) = portals[i];
+
+        // A portal through the apex doesn't narrow the funnel, but would flatten it into a line.
+        if on_segment(apex, portal_left, portal_right) {
+            i += 1;
+            continue;
+        }
+
+        // Narrow the right side, unless it would cross the left one, which is then a corner.
+        if orient2d(apex, right, portal_right) != Ordering::Less {
+            if apex == right || orient2d(apex, left, portal_right) == Ordering::Less {
+                right = portal_right;
+                right_index = i;
+            } else {
+                points.push(left);
+                apex = left;
+                (right, right_index) = (left, left_index);
+                i = left_index + 1;
+                continue;
+            }
+        }
+
+        // Narrow the left side, likewise.
+        if orient2d(apex, left, portal_left) != Ordering::Greater {
+            if apex == left || orient2d(apex, right, portal_left) == Ordering::Greater {
+                left = portal_left;
+                left_index = i;
+            } else {
+                points.push(right);
+                apex = right;
+                (left, left_index) = (right, right_index);
+                i = right_index + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    if points[points.len() - 1] != goal {
+        points.push(goal);
+    }
+    points.dedup();
+    points
+}
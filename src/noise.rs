@@ -0,0 +1,401 @@
+use crate::prelude::*;
+use crate::rng::QRng;
+use crate::vec2::QVec2;
+use fixed::types::I96F32;
+
+/// Skew factor of the 2D simplex grid, `(sqrt(3) - 1) / 2`.
+const F2: Q64 = Q64::lit("0.3660254037844386");
+/// Unskew factor of the 2D simplex grid, `(3 - sqrt(3)) / 6`.
+const G2: Q64 = Q64::lit("0.2113248654051871");
+/// Skew factor of the 3D simplex grid.
+const F3: Q64 = Q64::lit("0.3333333333333333");
+/// Unskew factor of the 3D simplex grid.
+const G3: Q64 = Q64::lit("0.1666666666666667");
+
+/// Gradient directions of the 2D noises.
+const GRAD2: [(i8, i8); 8] = [(1, 1), (-1, 1), (1, -1), (-1, -1), (1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// The lattice noise functions provided by [`QNoise`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QNoiseKind {
+    /// Ken Perlin's improved gradient noise.
+    Perlin,
+    /// Gradient noise on a simplex grid, cheaper than Perlin with fewer axis-aligned artifacts.
+    Simplex,
+    /// Interpolated random values on an integer grid.
+    Value,
+}
+
+/// Seeded gradient and value noise.
+///
+/// The permutation table is shuffled with [`QRng`] and every sample is computed in `Q64`, so the
+/// same seed and input produce bit-identical output on every platform. All samples are in the
+/// range `[-1, 1]` and the lattice repeats every 256 units.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::noise::{QNoise, QNoiseKind};
+/// use qmath::vec2::QVec2;
+///
+/// let noise = QNoise::from_seed(2024);
+/// assert_eq!(noise.perlin2(qvec2!(0.25, 0.75)).to_bits(), 264413184);
+/// assert_eq!(noise.perlin3(q64!(1.5), q64!(-2.25), q64!(0.5)).to_bits(), 1462239232);
+/// assert_eq!(noise.simplex2(qvec2!(3.1, -7.7)).to_bits(), 1764845320);
+/// assert_eq!(noise.simplex3(q64!(0.1), q64!(0.2), q64!(0.3)).to_bits(), 3355189568);
+/// assert_eq!(noise.value2(qvec2!(10.5, 20.25)).to_bits(), -2694716959);
+/// assert_eq!(noise.value3(q64!(10.5), q64!(20.25), q64!(-3)).to_bits(), 980315757);
+/// assert_eq!(noise.fbm2(QNoiseKind::Simplex, qvec2!(0.3, 0.6), 5, Q64::TWO, Q64::HALF).to_bits(), -317523787);
+///
+/// // Gradient noises vanish on the lattice.
+/// assert_eq!(noise.perlin2(qvec2!(17, -3)), Q64::ZERO);
+///
+/// // Huge coordinates and saturated frequencies don't overflow.
+/// assert!(noise.simplex2(qvec2!(1500000000, 1500000000)).abs() <= Q64::ONE);
+/// assert!(noise.simplex3(Q64::MAX, Q64::MIN, Q64::MAX).abs() <= Q64::ONE);
+/// assert!(noise.fbm2(QNoiseKind::Simplex, qvec2!(0.3, 0.6), 40, Q64::TWO, Q64::HALF).abs() <= Q64::ONE);
+/// assert!(noise.fbm3(QNoiseKind::Perlin, q64!(0.3), q64!(0.6), q64!(0.9), 40, Q64::TWO, Q64::HALF).abs() <= Q64::ONE);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QNoise {
+    perm: [u8; 512],
+}
+
+impl QNoise {
+    /// Creates the permutation table from a seed.
+    pub fn from_seed(seed: u32) -> Self {
+        let mut rng = QRng::from_seed(seed);
+        let mut table: [u8; 256] = core::array::from_fn(|i| i as u8);
+        rng.shuffle(&mut table);
+
+        let mut perm = [0; 512];
+        for (i, value) in perm.iter_mut().enumerate() {
+            *value = table[i & 255];
+        }
+        Self { perm }
+    }
+
+    /// Samples the noise of the given kind in 2D.
+    pub fn sample2(&self, kind: QNoiseKind, point: QVec2) -> Q64 {
+        match kind {
+            QNoiseKind::Perlin => self.perlin2(point),
+            QNoiseKind::Simplex => self.simplex2(point),
+            QNoiseKind::Value => self.value2(point),
+        }
+    }
+
+    /// Samples the noise of the given kind in 3D.
+    pub fn sample3(&self, kind: QNoiseKind, x: Q64, y: Q64, z: Q64) -> Q64 {
+        match kind {
+            QNoiseKind::Perlin => self.perlin3(x, y, z),
+            QNoiseKind::Simplex => self.simplex3(x, y, z),
+            QNoiseKind::Value => self.value3(x, y, z),
+        }
+    }
+
+    /// 2D Perlin noise.
+    pub fn perlin2(&self, point: QVec2) -> Q64 {
+        let (xi, x) = lattice(point.x);
+        let (yi, y) = lattice(point.y);
+        let u = fade(x);
+        let v = fade(y);
+
+        let a = self.perm[xi] as usize + yi;
+        let b = self.perm[xi + 1] as usize + yi;
+        let (aa, ab) = (self.perm[a], self.perm[a + 1]);
+        let (ba, bb) = (self.perm[b], self.perm[b + 1]);
+
+        lerp(
+            v,
+            lerp(u, grad2(aa, x, y), grad2(ba, x - Q64::ONE, y)),
+            lerp(u, grad2(ab, x, y - Q64::ONE), grad2(bb, x - Q64::ONE, y - Q64::ONE)),
+        )
+        .clamp(Q64::NEG_ONE, Q64::ONE)
+    }
+
+    /// 3D Perlin noise.
+    pub fn perlin3(&self, x: Q64, y: Q64, z: Q64) -> Q64 {
+        let (xi, x) = lattice(x);
+        let (yi, y) = lattice(y);
+        let (zi, z) = lattice(z);
+        let u = fade(x);
+        let v = fade(y);
+        let w = fade(z);
+        let (x1, y1, z1) = (x - Q64::ONE, y - Q64::ONE, z - Q64::ONE);
+
+        let a = self.perm[xi] as usize + yi;
+        let aa = self.perm[a] as usize + zi;
+        let ab = self.perm[a + 1] as usize + zi;
+        let b = self.perm[xi + 1] as usize + yi;
+        let ba = self.perm[b] as usize + zi;
+        let bb = self.perm[b + 1] as usize + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad3(self.perm[aa], x, y, z), grad3(self.perm[ba], x1, y, z)),
+                lerp(u, grad3(self.perm[ab], x, y1, z), grad3(self.perm[bb], x1, y1, z)),
+            ),
+            lerp(
+                v,
+                lerp(u, grad3(self.perm[aa + 1], x, y, z1), grad3(self.perm[ba + 1], x1, y, z1)),
+                lerp(u, grad3(self.perm[ab + 1], x, y1, z1), grad3(self.perm[bb + 1], x1, y1, z1)),
+            ),
+        )
+        .clamp(Q64::NEG_ONE, Q64::ONE)
+    }
+
+    /// 2D simplex noise.
+    pub fn simplex2(&self, point: QVec2) -> Q64 {
+        // Skews the input space to find the simplex cell, with room for the sums of large inputs.
+        let (x, y) = (I96F32::from_num(point.x), I96F32::from_num(point.y));
+        let skew = (x + y) * I96F32::from_num(F2);
+        let i = (x + skew).floor();
+        let j = (y + skew).floor();
+        let unskew = (i + j) * I96F32::from_num(G2);
+        let x0 = (x - (i - unskew)).to_num::<Q64>();
+        let y0 = (y - (j - unskew)).to_num::<Q64>();
+
+        let (i1, j1) = if x0 > y0 { (Q64::ONE, Q64::ZERO) } else { (Q64::ZERO, Q64::ONE) };
+        let (x1, y1) = (x0 - i1 + G2, y0 - j1 + G2);
+        let (x2, y2) = (x0 - Q64::ONE + G2 * 2, y0 - Q64::ONE + G2 * 2);
+
+        let ii = wrap_wide(i);
+        let jj = wrap_wide(j);
+        let (i1, j1) = (i1.to_num::<usize>(), j1.to_num::<usize>());
+        let h0 = self.perm[ii + self.perm[jj] as usize];
+        let h1 = self.perm[ii + i1 + self.perm[jj + j1] as usize];
+        let h2 = self.perm[ii + 1 + self.perm[jj + 1] as usize];
+
+        let sum = simplex_corner2(h0, x0, y0) + simplex_corner2(h1, x1, y1) + simplex_corner2(h2, x2, y2);
+        (sum * 70).clamp(Q64::NEG_ONE, Q64::ONE)
+    }
+
+    /// 3D simplex noise.
+    pub fn simplex3(&self, x: Q64, y: Q64, z: Q64) -> Q64 {
+        // Skews the input space to find the simplex cell, with room for the sums of large inputs.
+        let (x, y, z) = (I96F32::from_num(x), I96F32::from_num(y), I96F32::from_num(z));
+        let skew = (x + y + z) * I96F32::from_num(F3);
+        let i = (x + skew).floor();
+        let j = (y + skew).floor();
+        let k = (z + skew).floor();
+        let unskew = (i + j + k) * I96F32::from_num(G3);
+        let x0 = (x - (i - unskew)).to_num::<Q64>();
+        let y0 = (y - (j - unskew)).to_num::<Q64>();
+        let z0 = (z - (k - unskew)).to_num::<Q64>();
+
+        let (i1, j1, k1, i2, j2, k2) = if x0 >= y0 {
+            if y0 >= z0 {
+                (1, 0, 0, 1, 1, 0)
+            } else if x0 >= z0 {
+                (1, 0, 0, 1, 0, 1)
+            } else {
+                (0, 0, 1, 1, 0, 1)
+            }
+        } else if y0 < z0 {
+            (0, 0, 1, 0, 1, 1)
+        } else if x0 < z0 {
+            (0, 1, 0, 0, 1, 1)
+        } else {
+            (0, 1, 0, 1, 1, 0)
+        };
+
+        let corner = |offset_i: usize, offset_j: usize, offset_k: usize, scale: i64| {
+            (
+                x0 - Q64::from_num(offset_i) + G3 * scale,
+                y0 - Q64::from_num(offset_j) + G3 * scale,
+                z0 - Q64::from_num(offset_k) + G3 * scale,
+            )
+        };
+        let (x1, y1, z1) = corner(i1, j1, k1, 1);
+        let (x2, y2, z2) = corner(i2, j2, k2, 2);
+        let (x3, y3, z3) = corner(1, 1, 1, 3);
+
+        let ii = wrap_wide(i);
+        let jj = wrap_wide(j);
+        let kk = wrap_wide(k);
+        let hash = |di: usize, dj: usize, dk: usize| {
+            self.perm[ii + di + self.perm[jj + dj + self.perm[kk + dk] as usize] as usize]
+        };
+
+        let sum = simplex_corner3(hash(0, 0, 0), x0, y0, z0)
+            + simplex_corner3(hash(i1, j1, k1), x1, y1, z1)
+            + simplex_corner3(hash(i2, j2, k2), x2, y2, z2)
+            + simplex_corner3(hash(1, 1, 1), x3, y3, z3);
+        (sum * 32).clamp(Q64::NEG_ONE, Q64::ONE)
+    }
+
+    /// 2D value noise.
+    pub fn value2(&self, point: QVec2) -> Q64 {
+        let (xi, x) = lattice(point.x);
+        let (yi, y) = lattice(point.y);
+        let u = fade(x);
+        let v = fade(y);
+
+        let a = self.perm[xi] as usize + yi;
+        let b = self.perm[xi + 1] as usize + yi;
+
+        lerp(
+            v,
+            lerp(u, lattice_value(self.perm[a]), lattice_value(self.perm[b])),
+            lerp(u, lattice_value(self.perm[a + 1]), lattice_value(self.perm[b + 1])),
+        )
+    }
+
+    /// 3D value noise.
+    pub fn value3(&self, x: Q64, y: Q64, z: Q64) -> Q64 {
+        let (xi, x) = lattice(x);
+        let (yi, y) = lattice(y);
+        let (zi, z) = lattice(z);
+        let u = fade(x);
+        let v = fade(y);
+        let w = fade(z);
+
+        let a = self.perm[xi] as usize + yi;
+        let aa = self.perm[a] as usize + zi;
+        let ab = self.perm[a + 1] as usize + zi;
+        let b = self.perm[xi + 1] as usize + yi;
+        let ba = self.perm[b] as usize + zi;
+        let bb = self.perm[b + 1] as usize + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, lattice_value(self.perm[aa]), lattice_value(self.perm[ba])),
+                lerp(u, lattice_value(self.perm[ab]), lattice_value(self.perm[bb])),
+            ),
+            lerp(
+                v,
+                lerp(u, lattice_value(self.perm[aa + 1]), lattice_value(self.perm[ba + 1])),
+                lerp(u, lattice_value(self.perm[ab + 1]), lattice_value(self.perm[bb + 1])),
+            ),
+        )
+    }
+
+    /// 2D fractal Brownian motion.
+    ///
+    /// Sums `octaves` layers of noise, multiplying the frequency by `lacunarity` and the
+    /// amplitude by `gain` at each layer. The sum is divided by the total amplitude so the result
+    /// stays in `[-1, 1]`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `octaves` is zero or `gain` is not positive.
+    ///
+    /// SAT
+    pub fn fbm2(&self, kind: QNoiseKind, point: QVec2, octaves: u32, lacunarity: Q64, gain: Q64) -> Q64 {
+        assert!(octaves > 0, "[QNoise::fbm2] Expected at least one octave.");
+        assert!(gain > Q64::ZERO, "[QNoise::fbm2] Expected gain > 0.");
+        let mut sum = Q64::ZERO;
+        let mut amplitude = Q64::ONE;
+        let mut total_amplitude = Q64::ZERO;
+        let mut frequency = Q64::ONE;
+        for _ in 0..octaves {
+            let noise = self.sample2(kind, point.saturating_mul_num(frequency));
+            sum = sum.saturating_add(noise.saturating_mul(amplitude));
+            total_amplitude = total_amplitude.saturating_add(amplitude);
+            amplitude = amplitude.saturating_mul(gain);
+            frequency = frequency.saturating_mul(lacunarity);
+        }
+        sum.saturating_div(total_amplitude)
+    }
+
+    /// 3D fractal Brownian motion, see [`fbm2`][Self::fbm2()].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `octaves` is zero or `gain` is not positive.
+    ///
+    /// SAT
+    #[allow(clippy::too_many_arguments)]
+    pub fn fbm3(&self, kind: QNoiseKind, x: Q64, y: Q64, z: Q64, octaves: u32, lacunarity: Q64, gain: Q64) -> Q64 {
+        assert!(octaves > 0, "[QNoise::fbm3] Expected at least one octave.");
+        assert!(gain > Q64::ZERO, "[QNoise::fbm3] Expected gain > 0.");
+        let mut sum = Q64::ZERO;
+        let mut amplitude = Q64::ONE;
+        let mut total_amplitude = Q64::ZERO;
+        let mut frequency = Q64::ONE;
+        for _ in 0..octaves {
+            let noise = self.sample3(
+                kind,
+                x.saturating_mul(frequency),
+                y.saturating_mul(frequency),
+                z.saturating_mul(frequency),
+            );
+            sum = sum.saturating_add(noise.saturating_mul(amplitude));
+            total_amplitude = total_amplitude.saturating_add(amplitude);
+            amplitude = amplitude.saturating_mul(gain);
+            frequency = frequency.saturating_mul(lacunarity);
+        }
+        sum.saturating_div(total_amplitude)
+    }
+}
+
+/// Splits a coordinate into its lattice cell, wrapped to `[0, 256)`, and the offset inside it.
+fn lattice(value: Q64) -> (usize, Q64) {
+    let floor = value.floor();
+    (wrap(floor), value - floor)
+}
+
+/// Wraps an integral coordinate to `[0, 256)`.
+fn wrap(floor: Q64) -> usize {
+    (floor.to_num::<i32>() & 255) as usize
+}
+
+/// Wraps an integral coordinate of the skewed simplex grid to `[0, 256)`.
+fn wrap_wide(floor: I96F32) -> usize {
+    (floor.to_num::<i128>() & 255) as usize
+}
+
+/// Perlin's quintic smoothstep `6t^5 - 15t^4 + 10t^3`.
+fn fade(t: Q64) -> Q64 {
+    t * t * t * (t * (t * 6 - Q64::from_num(15)) + Q64::from_num(10))
+}
+
+fn lerp(t: Q64, a: Q64, b: Q64) -> Q64 {
+    a + (b - a) * t
+}
+
+/// Maps a hash to a lattice value in `[-1, 1]`.
+fn lattice_value(hash: u8) -> Q64 {
+    Q64::from_num(hash) / 255 * 2 - Q64::ONE
+}
+
+fn grad2(hash: u8, x: Q64, y: Q64) -> Q64 {
+    let (gx, gy) = GRAD2[(hash & 7) as usize];
+    x * gx as i64 + y * gy as i64
+}
+
+fn grad3(hash: u8, x: Q64, y: Q64, z: Q64) -> Q64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+fn simplex_corner2(hash: u8, x: Q64, y: Q64) -> Q64 {
+    let t = Q64::HALF - x * x - y * y;
+    if t < Q64::ZERO {
+        return Q64::ZERO;
+    }
+    let t2 = t * t;
+    t2 * t2 * grad2(hash, x, y)
+}
+
+fn simplex_corner3(hash: u8, x: Q64, y: Q64, z: Q64) -> Q64 {
+    let t = Q64::lit("0.6") - x * x - y * y - z * z;
+    if t < Q64::ZERO {
+        return Q64::ZERO;
+    }
+    let t2 = t * t;
+    t2 * t2 * grad3(hash, x, y, z)
+}
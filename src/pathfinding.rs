@@ -0,0 +1,496 @@
+use crate::prelude::*;
+use crate::vec2::QVec2;
+use core::cmp::Reverse;
+use serde::{Deserialize, Serialize};
+use std::collections::BinaryHeap;
+
+/// Moves allowed between grid cells.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QConnectivity {
+    /// Horizontal and vertical moves only.
+    Four,
+    /// Diagonal moves too, but only past two walkable cells, so paths never cut corners.
+    #[default]
+    Eight,
+}
+
+/// Estimates of the remaining cost to the goal, in cells.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QHeuristic {
+    /// Sum of the axis distances. Exact on open four-connected grids, but overestimates on
+    /// eight-connected ones, which can give longer paths.
+    Manhattan,
+    /// Diagonal steps first, then straight ones. Exact on open eight-connected grids.
+    #[default]
+    Octile,
+    /// Straight-line distance, from `QVec2::distance`.
+    Euclidean,
+}
+
+impl QHeuristic {
+    /// Returns the estimated cost between two cells.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::pathfinding::QHeuristic;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let (a, b) = (qvec2!(0, 0), qvec2!(3, 4));
+    /// assert_eq!(QHeuristic::Manhattan.estimate(a, b), q64!(7));
+    /// assert_eq!(QHeuristic::Octile.estimate(a, b), Q64::SQRT_2 * 3 + q64!(1));
+    /// assert_eq!(QHeuristic::Euclidean.estimate(a, b), q64!(5));
+    /// ```
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn estimate(self, from: QVec2, to: QVec2) -> Q64 {
+        match self {
+            QHeuristic::Manhattan => {
+                let d = to.saturating_sub(from).abs();
+                d.x.saturating_add(d.y)
+            }
+            QHeuristic::Octile => {
+                let d = to.saturating_sub(from).abs();
+                let (min, max) = if d.x < d.y { (d.x, d.y) } else { (d.y, d.x) };
+                min.saturating_mul(Q64::SQRT_2).saturating_add(max - min)
+            }
+            QHeuristic::Euclidean => from.distance(to),
+        }
+    }
+}
+
+/// A path through a grid.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QGridPath {
+    /// Every cell from the start to the goal, each a move from the one before.
+    pub cells: Vec<(u32, u32)>,
+    /// Total cost of the moves, in cells whatever the cell size.
+    pub cost: Q64,
+}
+
+/// A rectangular grid of cells with traversal costs, for A* pathfinding.
+///
+/// Entering a cell costs its cost times the length of the move, one straight or `SQRT_2`
+/// diagonally. Costs are at least one, so the octile and Euclidean heuristics never overestimate
+/// a move, and the search breaks ties by the estimate and then by the cell, lowest row first, so
+/// every machine finds the same path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawGrid")]
+pub struct QGrid {
+    /// Position of the corner of cell `(0, 0)`.
+    pub origin: QVec2,
+    cell_size: Q64,
+    width: u32,
+    height: u32,
+    /// Cost of each cell by row, zero when blocked.
+    costs: Vec<Q64>,
+    /// Number of cells with a cost other than one.
+    weighted: usize,
+}
+
+/// The serialized form of a [`QGrid`], checked before it becomes one. The count of weighted
+/// cells is recomputed rather than trusted.
+#[derive(Deserialize)]
+struct RawGrid {
+    origin: QVec2,
+    cell_size: Q64,
+    width: u32,
+    height: u32,
+    costs: Vec<Q64>,
+}
+
+impl TryFrom<RawGrid> for QGrid {
+    type Error = &'static str;
+
+    fn try_from(raw: RawGrid) -> Result<Self, Self::Error> {
+        if raw.width == 0 || raw.height == 0 {
+            return Err("[QGrid::deserialize] Expected a non-empty grid.");
+        }
+        if raw.cell_size <= Q64::ZERO {
+            return Err("[QGrid::deserialize] Expected cell_size > 0.");
+        }
+        if raw.costs.len() != raw.width as usize * raw.height as usize {
+            return Err("[QGrid::deserialize] Expected a cost for every cell.");
+        }
+        if raw.costs.iter().any(|&cost| cost != Q64::ZERO && cost < Q64::ONE) {
+            return Err("[QGrid::deserialize] Expected costs of zero or at least one.");
+        }
+        let weighted = raw.costs.iter().filter(|&&cost| cost > Q64::ONE).count();
+        Ok(Self { origin: raw.origin, cell_size: raw.cell_size, width: raw.width, height: raw.height, costs: raw.costs, weighted })
+    }
+}
+
+const STRAIGHT: [(i64, i64); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const DIAGONAL: [(i64, i64); 4] = [(1, 1), (-1, 1), (1, -1), (-1, -1)];
+
+impl QGrid {
+    /// Creates a grid of walkable cells of cost one, with unit cells from the origin.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `width` or `height` is zero.
+    #[must_use]
+    pub fn new(width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "[QGrid::new] Expected a non-empty grid.");
+        Self {
+            origin: QVec2::ZERO,
+            cell_size: Q64::ONE,
+            width,
+            height,
+            costs: vec![Q64::ONE; width as usize * height as usize],
+            weighted: 0,
+        }
+    }
+
+    /// Returns the side of the square cells in world units.
+    #[inline]
+    #[must_use]
+    pub fn cell_size(&self) -> Q64 {
+        self.cell_size
+    }
+
+    /// Sets the side of the square cells in world units.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::pathfinding::QGrid;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut grid = QGrid::new(4, 4);
+    /// grid.set_cell_size(q64!(0.5));
+    /// assert_eq!(grid.cell_at(qvec2!(1.2, 0.3)), Some((2, 0)));
+    /// assert_eq!(grid.cell_center(2, 0), qvec2!(1.25, 0.25));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `cell_size` is not positive.
+    pub fn set_cell_size(&mut self, cell_size: Q64) {
+        assert!(cell_size > Q64::ZERO, "[QGrid::set_cell_size] Expected cell_size > 0.");
+        self.cell_size = cell_size;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the index of a cell, or `None` if it is out of bounds.
+    fn index(&self, x: i64, y: i64) -> Option<usize> {
+        (0..i64::from(self.width)).contains(&x).then_some(())?;
+        (0..i64::from(self.height)).contains(&y).then_some(())?;
+        Some(y as usize * self.width as usize + x as usize)
+    }
+
+    fn cell(&self, index: usize) -> (i64, i64) {
+        ((index % self.width as usize) as i64, (index / self.width as usize) as i64)
+    }
+
+    /// Returns the cost of entering a cell, or `None` if it is blocked or out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn cost(&self, x: u32, y: u32) -> Option<Q64> {
+        self.cost_at(i64::from(x), i64::from(y))
+    }
+
+    fn cost_at(&self, x: i64, y: i64) -> Option<Q64> {
+        self.index(x, y).map(|i| self.costs[i]).filter(|&cost| cost != Q64::ZERO)
+    }
+
+    /// Returns true if a cell is in bounds and not blocked.
+    #[inline]
+    #[must_use]
+    pub fn is_walkable(&self, x: u32, y: u32) -> bool {
+        self.cost(x, y).is_some()
+    }
+
+    fn walkable(&self, x: i64, y: i64) -> bool {
+        self.cost_at(x, y).is_some()
+    }
+
+    /// Sets the cost of entering a cell.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the cell is out of bounds or `cost` is less than one.
+    pub fn set_cost(&mut self, x: u32, y: u32, cost: Q64) {
+        assert!(cost >= Q64::ONE, "[QGrid::set_cost] Expected cost >= 1.");
+        self.replace(x, y, cost);
+    }
+
+    /// Blocks a cell, or makes it walkable again with a cost of one.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the cell is out of bounds.
+    pub fn set_walkable(&mut self, x: u32, y: u32, walkable: bool) {
+        self.replace(x, y, if walkable { Q64::ONE } else { Q64::ZERO });
+    }
+
+    fn replace(&mut self, x: u32, y: u32, cost: Q64) {
+        let index = self.index(i64::from(x), i64::from(y)).expect("[QGrid::replace] Cell out of bounds.");
+        let old = core::mem::replace(&mut self.costs[index], cost);
+        self.weighted = self.weighted + usize::from(cost > Q64::ONE) - usize::from(old > Q64::ONE);
+    }
+
+    /// Returns the world position of the center of a cell.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn cell_center(&self, x: u32, y: u32) -> QVec2 {
+        let local = QVec2::new(Q64::from_num(x), Q64::from_num(y)).saturating_add_num(Q64::HALF);
+        self.origin.saturating_add(local.saturating_mul_num(self.cell_size))
+    }
+
+    /// Returns the cell containing a world position, or `None` if it is outside the grid.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn cell_at(&self, point: QVec2) -> Option<(u32, u32)> {
+        let local = point.saturating_sub(self.origin).saturating_div_num(self.cell_size).floor();
+        let (x, y) = (local.x.to_num::<i64>(), local.y.to_num::<i64>());
+        self.index(x, y)?;
+        Some((x as u32, y as u32))
+    }
+
+    /// Returns the centers of the cells of a path.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn path_points(&self, path: &QGridPath) -> Vec<QVec2> {
+        path.cells.iter().map(|&(x, y)| self.cell_center(x, y)).collect()
+    }
+
+    /// Returns true if the move from `(x, y)` by `(dx, dy)` is allowed.
+    fn can_move(&self, x: i64, y: i64, dx: i64, dy: i64) -> bool {
+        self.walkable(x + dx, y + dy) && (dx == 0 || dy == 0 || (self.walkable(x + dx, y) && self.walkable(x, y + dy)))
+    }
+
+    /// Finds the cheapest path from `start` to `goal` with A*, or `None` if there is none or an end
+    /// is blocked or out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::pathfinding::{QConnectivity, QGrid, QHeuristic};
+    /// use qmath::vec2::QVec2;
+    ///
+    /// // A wall with a gap at the top.
+    /// let mut grid = QGrid::new(5, 5);
+    /// for y in 0..4 {
+    ///     grid.set_walkable(2, y, false);
+    /// }
+    ///
+    /// let path = grid.find_path((0, 0), (4, 0), QConnectivity::Four, QHeuristic::Manhattan).unwrap();
+    /// assert_eq!(path.cost, q64!(12));
+    /// assert_eq!(path.cells.len(), 13);
+    /// assert!(path.cells.contains(&(2, 4)));
+    ///
+    /// let path = grid.find_path((0, 0), (4, 0), QConnectivity::Eight, QHeuristic::Octile).unwrap();
+    /// assert_eq!(path.cost, q64!(8) + Q64::SQRT_2 * 2);
+    /// assert_eq!(grid.path_points(&path)[0], qvec2!(0.5, 0.5));
+    ///
+    /// // Expensive mud makes the search go around it.
+    /// let mut grid = QGrid::new(3, 3);
+    /// grid.set_cost(1, 0, q64!(5));
+    /// let path = grid.find_path((0, 0), (2, 0), QConnectivity::Four, QHeuristic::Euclidean).unwrap();
+    /// assert_eq!(path.cells, vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 0)]);
+    ///
+    /// grid.set_walkable(2, 0, false);
+    /// assert_eq!(grid.find_path((0, 0), (2, 0), QConnectivity::Four, QHeuristic::Euclidean), None);
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn find_path(&self, start: (u32, u32), goal: (u32, u32), connectivity: QConnectivity, heuristic: QHeuristic) -> Option<QGridPath> {
+        let moves: &[(i64, i64)] = match connectivity {
+            QConnectivity::Four => &STRAIGHT,
+            QConnectivity::Eight => &[STRAIGHT, DIAGONAL].concat(),
+        };
+        self.search(start, goal, heuristic, |grid, index, _parent, successors| {
+            let (x, y) = grid.cell(index);
+            for &(dx, dy) in moves {
+                if grid.can_move(x, y, dx, dy) {
+                    let length = if dx != 0 && dy != 0 { Q64::SQRT_2 } else { Q64::ONE };
+                    let cost = grid.costs[grid.index(x + dx, y + dy).unwrap()];
+                    successors.push((x + dx, y + dy, cost.saturating_mul(length)));
+                }
+            }
+        })
+    }
+
+    /// Finds the cheapest eight-connected path from `start` to `goal` with Jump Point Search, or
+    /// `None` if there is none or an end is blocked or out of bounds.
+    ///
+    /// Expands far fewer cells than [`QGrid::find_path`] on open maps and finds a path of the same
+    /// cost, though equally cheap paths may be chosen differently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::pathfinding::{QConnectivity, QGrid, QHeuristic};
+    ///
+    /// let mut grid = QGrid::new(32, 32);
+    /// for y in 0..30 {
+    ///     grid.set_walkable(16, y, false);
+    /// }
+    /// let jps = grid.find_path_jps((0, 0), (31, 0), QHeuristic::Octile).unwrap();
+    /// let astar = grid.find_path((0, 0), (31, 0), QConnectivity::Eight, QHeuristic::Octile).unwrap();
+    /// assert_eq!(jps.cost, astar.cost);
+    /// assert_eq!(jps.cells.first(), Some(&(0, 0)));
+    /// assert_eq!(jps.cells.last(), Some(&(31, 0)));
+    /// assert!(jps.cells.windows(2).all(|w| w[0].0.abs_diff(w[1].0) <= 1 && w[0].1.abs_diff(w[1].1) <= 1));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if any cell costs more than one, as jumps assume uniform costs.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn find_path_jps(&self, start: (u32, u32), goal: (u32, u32), heuristic: QHeuristic) -> Option<QGridPath> {
+        assert!(self.weighted == 0, "[QGrid::find_path_jps] Expected uniform costs.");
+        let goal_cell = (i64::from(goal.0), i64::from(goal.1));
+        self.search(start, goal, heuristic, |grid, index, parent, successors| {
+            let (x, y) = grid.cell(index);
+            for (dx, dy) in grid.pruned_directions(x, y, parent.map(|p| grid.cell(p))) {
+                if let Some((jx, jy)) = grid.jump(x + dx, y + dy, dx, dy, goal_cell) {
+                    let (ax, ay) = ((jx - x).abs(), (jy - y).abs());
+                    let (min, max) = (ax.min(ay), ax.max(ay));
+                    let cost = Q64::SQRT_2.saturating_mul(Q64::from_num(min)).saturating_add(Q64::from_num(max - min));
+                    successors.push((jx, jy, cost));
+                }
+            }
+        })
+    }
+
+    /// Returns the directions worth searching from a cell reached from `parent`.
+    fn pruned_directions(&self, x: i64, y: i64, parent: Option<(i64, i64)>) -> Vec<(i64, i64)> {
+        let Some((px, py)) = parent else {
+            return STRAIGHT.into_iter().chain(DIAGONAL).filter(|&(dx, dy)| self.can_move(x, y, dx, dy)).collect();
+        };
+        let (dx, dy) = ((x - px).signum(), (y - py).signum());
+        let mut directions = Vec::new();
+        if dx != 0 && dy != 0 {
+            directions.extend([(0, dy), (dx, 0), (dx, dy)]);
+        } else if dx != 0 {
+            directions.extend([(dx, 0), (dx, 1), (dx, -1), (0, 1), (0, -1)]);
+        } else {
+            directions.extend([(0, dy), (1, dy), (-1, dy), (1, 0), (-1, 0)]);
+        }
+        directions.retain(|&(dx, dy)| self.can_move(x, y, dx, dy));
+        directions
+    }
+
+    /// Walks from `(x, y)` in direction `(dx, dy)` and returns the first jump point: the goal, a
+    /// cell with a forced neighbor, or a diagonal cell from which a straight jump succeeds.
+    fn jump(&self, mut x: i64, mut y: i64, dx: i64, dy: i64, goal: (i64, i64)) -> Option<(i64, i64)> {
+        loop {
+            if !self.walkable(x, y) {
+                return None;
+            }
+            if (x, y) == goal {
+                return Some((x, y));
+            }
+            if dx != 0 && dy != 0 {
+                if self.jump(x + dx, y, dx, 0, goal).is_some() || self.jump(x, y + dy, 0, dy, goal).is_some() {
+                    return Some((x, y));
+                }
+            } else if dx != 0 {
+                let forced = |side: i64| self.walkable(x, y + side) && !self.walkable(x - dx, y + side);
+                if forced(1) || forced(-1) {
+                    return Some((x, y));
+                }
+            } else {
+                let forced = |side: i64| self.walkable(x + side, y) && !self.walkable(x + side, y - dy);
+                if forced(1) || forced(-1) {
+                    return Some((x, y));
+                }
+            }
+            if !self.can_move(x, y, dx, dy) {
+                return None;
+            }
+            x += dx;
+            y += dy;
+        }
+    }
+
+    /// Runs A* with successors given by `expand` as `(x, y, step cost)` and returns the path with
+    /// straight and diagonal runs between successors filled in.
+    fn search<F>(&self, start: (u32, u32), goal: (u32, u32), heuristic: QHeuristic, mut expand: F) -> Option<QGridPath>
+    where
+        F: FnMut(&Self, usize, Option<usize>, &mut Vec<(i64, i64, Q64)>),
+    {
+        let start_index = self.index(i64::from(start.0), i64::from(start.1)).filter(|&i| self.costs[i] != Q64::ZERO)?;
+        let goal_index = self.index(i64::from(goal.0), i64::from(goal.1)).filter(|&i| self.costs[i] != Q64::ZERO)?;
+        let goal_point = QVec2::new(Q64::from_num(goal.0), Q64::from_num(goal.1));
+        let estimate = |(x, y): (i64, i64)| heuristic.estimate(QVec2::new(Q64::from_num(x), Q64::from_num(y)), goal_point);
+
+        let mut costs = vec![Q64::MAX; self.costs.len()];
+        let mut parents = vec![usize::MAX; self.costs.len()];
+        let mut closed = vec![false; self.costs.len()];
+        let mut open = BinaryHeap::new();
+        let mut successors = Vec::new();
+        costs[start_index] = Q64::ZERO;
+        let h = estimate(self.cell(start_index));
+        open.push(Reverse((h, h, start_index)));
+
+        while let Some(Reverse((_, _, index))) = open.pop() {
+            if closed[index] {
+                continue;
+            }
+            if index == goal_index {
+                return Some(self.trace(&parents, index, costs[index]));
+            }
+            closed[index] = true;
+            let parent = (parents[index] != usize::MAX).then_some(parents[index]);
+            successors.clear();
+            expand(self, index, parent, &mut successors);
+            for &(x, y, step) in &successors {
+                let next = self.index(x, y).unwrap();
+                let cost = costs[index].saturating_add(step);
+                if closed[next] || cost >= costs[next] {
+                    continue;
+                }
+                costs[next] = cost;
+                parents[next] = index;
+                let h = estimate((x, y));
+                open.push(Reverse((cost.saturating_add(h), h, next)));
+            }
+        }
+        None
+    }
+
+    /// Rebuilds the path ending at `index`, one cell per move.
+    fn trace(&self, parents: &[usize], mut index: usize, cost: Q64) -> QGridPath {
+        let mut cells = Vec::new();
+        let (mut x, mut y) = self.cell(index);
+        while parents[index] != usize::MAX {
+            index = parents[index];
+            let (px, py) = self.cell(index);
+            while (x, y) != (px, py) {
+                cells.push((x as u32, y as u32));
+                x -= (x - px).signum();
+                y -= (y - py).signum();
+            }
+        }
+        cells.push((x as u32, y as u32));
+        cells.reverse();
+        QGridPath { cells, cost }
+    }
+}
@@ -0,0 +1,551 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::joints::{QJoint, Row, solve_row};
+use crate::polygon::QPolygon;
+use crate::sat::{QConvex, QManifold, collide};
+use crate::shapes::QCircle;
+use crate::vec2::QVec2;
+use crate::wide::I320;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Solver iterations of a new world.
+const DEFAULT_ITERATIONS: u32 = 8;
+
+/// Fraction of the penetration, or joint error, removed per step.
+pub(crate) const BAUMGARTE: Q64 = Q64::lit("0.2");
+
+/// Penetration allowed without correction, so resting contacts stay in touch.
+const LINEAR_SLOP: Q64 = Q64::lit("0.005");
+
+/// Approach speed below which contacts don't bounce, so resting bodies settle.
+const RESTITUTION_THRESHOLD: Q64 = Q64::lit("0.5");
+
+/// A rigid body.
+///
+/// The shape is given in body space, around the body origin, which is also its center of mass
+/// and its center of rotation. A body with a zero inverse mass and inertia is static.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QBody {
+    pub shape: QConvex,
+    pub position: QVec2,
+    pub velocity: QVec2,
+    /// Angle in radians, counter-clockwise.
+    pub angle: Q64,
+    pub angular_velocity: Q64,
+    pub inv_mass: Q64,
+    pub inv_inertia: Q64,
+    /// Bounciness, from `0` for none to `1` for a perfect bounce.
+    pub restitution: Q64,
+    /// Coulomb friction coefficient.
+    pub friction: Q64,
+    /// Force accumulated until the next step.
+    pub force: QVec2,
+    /// Torque accumulated until the next step.
+    pub torque: Q64,
+}
+
+impl QBody {
+    /// Creates a dynamic body of uniform density with the given `mass`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::physics::QBody;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// // A square of side `s` has the moment of inertia `m * s^2 / 6`.
+    /// let tile = QBody::new(QAabb2::new(qvec2!(-1, -1), qvec2!(1, 1)).into(), QVec2::ZERO, q64!(1));
+    /// assert!(tile.inv_inertia.abs_diff(q64!(1.5)) <= q64!(0.000000001));
+    /// let hangar = QBody::new(QAabb2::new(qvec2!(-400, -400), qvec2!(400, 400)).into(), QVec2::ZERO, q64!(1));
+    /// assert!(hangar.inv_inertia.abs_diff(q64!(0.000009375)) <= q64!(0.000000001));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `mass` is not positive.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn new(shape: QConvex, position: QVec2, mass: Q64) -> Self {
+        assert!(mass > Q64::ZERO, "[QBody::new] Expected mass > 0.");
+        let inertia = match &shape {
+            QConvex::Circle(circle) => {
+                let radius_squared = circle.radius.saturating_mul(circle.radius);
+                mass.saturating_mul(radius_squared.half().saturating_add(circle.center.length_squared()))
+            }
+            QConvex::Polygon(polygon) => mass.saturating_mul(polygon_unit_inertia(polygon)),
+        };
+        Self {
+            inv_mass: mass.saturating_recip(),
+            inv_inertia: if inertia > Q64::ZERO { inertia.saturating_recip() } else { Q64::ZERO },
+            ..Self::new_static(shape, position)
+        }
+    }
+
+    /// Creates a static body, which never moves.
+    #[must_use]
+    pub fn new_static(shape: QConvex, position: QVec2) -> Self {
+        Self {
+            shape,
+            position,
+            velocity: QVec2::ZERO,
+            angle: Q64::ZERO,
+            angular_velocity: Q64::ZERO,
+            inv_mass: Q64::ZERO,
+            inv_inertia: Q64::ZERO,
+            restitution: Q64::ZERO,
+            friction: Q64::lit("0.5"),
+            force: QVec2::ZERO,
+            torque: Q64::ZERO,
+        }
+    }
+
+    /// Returns whether the body is static.
+    #[inline]
+    #[must_use]
+    pub fn is_static(&self) -> bool {
+        self.inv_mass == Q64::ZERO && self.inv_inertia == Q64::ZERO
+    }
+
+    /// Returns the unit vector of the body angle.
+    #[inline]
+    #[must_use]
+    pub fn rotation(&self) -> QVec2 {
+        QVec2::from_angle(self.angle)
+    }
+
+    /// Transforms `point` from body space to world space.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn to_world(&self, point: QVec2) -> QVec2 {
+        self.position.saturating_add(self.rotation().rotate(point))
+    }
+
+    /// Transforms `point` from world space to body space.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn to_local(&self, point: QVec2) -> QVec2 {
+        let rotation = self.rotation();
+        QVec2::new(rotation.x, -rotation.y).rotate(point.saturating_sub(self.position))
+    }
+
+    /// Returns the shape in world space.
+    #[must_use]
+    pub fn world_shape(&self) -> QConvex {
+        let rotation = self.rotation();
+        let to_world = |p: QVec2| self.position.saturating_add(rotation.rotate(p));
+        match &self.shape {
+            QConvex::Circle(circle) => QConvex::Circle(QCircle::new(to_world(circle.center), circle.radius)),
+            QConvex::Polygon(polygon) => QConvex::Polygon(QPolygon::new(polygon.vertices().iter().map(|p| to_world(*p)).collect())),
+        }
+    }
+
+    /// Returns the velocity of the body at the world point `point`.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn velocity_at(&self, point: QVec2) -> QVec2 {
+        let r = point.saturating_sub(self.position);
+        self.velocity.saturating_add(r.perp().saturating_mul_num(self.angular_velocity))
+    }
+
+    /// Adds a force applied at the world point `point` until the next step.
+    ///
+    /// SAT
+    pub fn apply_force(&mut self, force: QVec2, point: QVec2) {
+        self.force = self.force.saturating_add(force);
+        self.torque = self.torque.saturating_add(point.saturating_sub(self.position).cross(force));
+    }
+
+    /// Changes the velocity by an impulse applied at the world point `point`.
+    ///
+    /// SAT
+    pub fn apply_impulse(&mut self, impulse: QVec2, point: QVec2) {
+        self.velocity = self.velocity.saturating_add(impulse.saturating_mul_num(self.inv_mass));
+        let torque = point.saturating_sub(self.position).cross(impulse);
+        self.angular_velocity = self.angular_velocity.saturating_add(torque.saturating_mul(self.inv_inertia));
+    }
+}
+
+/// Returns the moment of inertia of a polygon of unit mass about the body origin.
+///
+/// The sums are exact on the raw bits, as the terms grow with the fourth power of the size.
+///
+/// SAT
+fn polygon_unit_inertia(polygon: &QPolygon) -> Q64 {
+    let vertices = polygon.vertices();
+    let n = vertices.len();
+    let mut numerator = I320::ZERO;
+    let mut denominator = I320::ZERO;
+    for i in 0..n {
+        let (p, q) = (vertices[i], vertices[(i + 1) % n]);
+        let [px, py, qx, qy] = [p.x, p.y, q.x, q.y].map(I320::from_bits);
+        let cross = px * qy - py * qx;
+        let sum = px * px + py * py + px * qx + py * qy + qx * qx + qy * qy;
+        numerator = numerator + cross * sum;
+        denominator = denominator + cross;
+    }
+    if denominator == I320::ZERO {
+        return Q64::ZERO;
+    }
+    // The numerator is scaled by 2^128 and the denominator by 2^64, so 2^32 is left to remove.
+    numerator.div_round(denominator * I320::from_i128(6 << 32)).saturating_to_q64()
+}
+
+/// Returns the bounding box of a world-space shape.
+fn shape_aabb(shape: &QConvex) -> QAabb2 {
+    match shape {
+        QConvex::Circle(circle) => circle.aabb(),
+        QConvex::Polygon(polygon) => polygon.aabb(),
+    }
+}
+
+/// A contact point being solved, with the impulses accumulated over the iterations.
+#[derive(Debug, Clone, Copy)]
+struct ContactPoint {
+    r_a: QVec2,
+    r_b: QVec2,
+    normal_mass: Q64,
+    tangent_mass: Q64,
+    bias: Q64,
+    normal_impulse: Q64,
+    tangent_impulse: Q64,
+}
+
+/// The contact of two bodies for one step.
+#[derive(Debug, Clone)]
+struct Contact {
+    a: usize,
+    b: usize,
+    normal: QVec2,
+    friction: Q64,
+    points: Vec<ContactPoint>,
+}
+
+/// A world of rigid bodies stepped in lockstep.
+///
+/// Every step runs in a fixed order: forces and gravity are integrated into velocities, contacts
+/// are found between every pair of bodies in index order, joints and then contacts are resolved by
+/// sequential impulses, then velocities are integrated into positions. Bodies sharing a joint
+/// don't collide. Since all of it is fixed-point arithmetic, the
+/// same inputs give bit-identical results on every platform.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QWorld {
+    pub gravity: QVec2,
+    /// Velocity iterations of the impulse solver per step, for both joints and contacts.
+    pub iterations: u32,
+    bodies: Vec<QBody>,
+    joints: Vec<QJoint>,
+}
+
+impl Default for QWorld {
+    fn default() -> Self {
+        Self::new(QVec2::ZERO)
+    }
+}
+
+impl QWorld {
+    /// Creates an empty world.
+    #[must_use]
+    pub fn new(gravity: QVec2) -> Self {
+        Self { gravity, iterations: DEFAULT_ITERATIONS, bodies: Vec::new(), joints: Vec::new() }
+    }
+
+    /// Adds a body and returns its index.
+    pub fn add_body(&mut self, body: QBody) -> usize {
+        self.bodies.push(body);
+        self.bodies.len() - 1
+    }
+
+    /// Adds a joint and returns its index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::joints::{QDistanceJoint, QRevoluteJoint, QSpringJoint};
+    /// use qmath::physics::{QBody, QWorld};
+    /// use qmath::shapes::QCircle;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut world = QWorld::new(qvec2!(0, -10));
+    /// world.iterations = 12;
+    /// let ball = QCircle::new(QVec2::ZERO, q64!(0.25));
+    /// let ceiling = world.add_body(QBody::new_static(ball.into(), qvec2!(0, 10)));
+    ///
+    /// // A pendulum on a rod, starting sideways.
+    /// let bob = world.add_body(QBody::new(ball.into(), qvec2!(2, 10), q64!(1)));
+    /// world.add_joint(QDistanceJoint::new(&world, ceiling, bob, qvec2!(0, 10), qvec2!(2, 10)));
+    ///
+    /// // A chain of two links hinged together.
+    /// let link = world.add_body(QBody::new(ball.into(), qvec2!(-1, 10), q64!(1)));
+    /// let end = world.add_body(QBody::new(ball.into(), qvec2!(-2, 10), q64!(1)));
+    /// world.add_joint(QRevoluteJoint::new(&world, ceiling, link, qvec2!(0, 10)));
+    /// world.add_joint(QRevoluteJoint::new(&world, link, end, qvec2!(-1, 10)));
+    ///
+    /// // A weight on a spring, at rest one unit below the ceiling.
+    /// let weight = world.add_body(QBody::new(ball.into(), qvec2!(5, 9), q64!(1)));
+    /// world.add_joint(QSpringJoint::new(&world, ceiling, weight, qvec2!(5, 10), qvec2!(5, 9), q64!(100), q64!(5)));
+    ///
+    /// for _ in 0..300 {
+    ///     world.step(q64!(1) / 60);
+    /// }
+    /// let length = world.body(bob).position.distance(qvec2!(0, 10));
+    /// assert!((length - q64!(2)).abs() < q64!(0.01));
+    /// let link_length = world.body(end).position.distance(world.body(link).position);
+    /// assert!((link_length - q64!(1)).abs() < q64!(0.01));
+    /// // Gravity stretches the spring by `m * g / k`.
+    /// assert!((world.body(weight).position.y - q64!(8.9)).abs() < q64!(0.01));
+    /// ```
+    pub fn add_joint(&mut self, joint: impl Into<QJoint>) -> usize {
+        self.joints.push(joint.into());
+        self.joints.len() - 1
+    }
+
+    /// Returns the joints, by index.
+    #[inline]
+    #[must_use]
+    pub fn joints(&self) -> &[QJoint] {
+        &self.joints
+    }
+
+    /// Returns the bodies, by index.
+    #[inline]
+    #[must_use]
+    pub fn bodies(&self) -> &[QBody] {
+        &self.bodies
+    }
+
+    /// Returns the body at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn body(&self, index: usize) -> &QBody {
+        &self.bodies[index]
+    }
+
+    /// Returns the body at `index` mutably.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn body_mut(&mut self, index: usize) -> &mut QBody {
+        &mut self.bodies[index]
+    }
+
+    /// Advances the world by `dt` seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::physics::{QBody, QWorld};
+    /// use qmath::sat::QConvex;
+    /// use qmath::shapes::QCircle;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut world = QWorld::new(qvec2!(0, -10));
+    /// let ground = QConvex::from(QAabb2::new(qvec2!(-10, -1), qvec2!(10, 0)));
+    /// world.add_body(QBody::new_static(ground, QVec2::ZERO));
+    /// let ball = world.add_body(QBody::new(QCircle::new(QVec2::ZERO, q64!(0.5)).into(), qvec2!(0, 3), q64!(1)));
+    /// let crate_box = QConvex::from(QAabb2::new(qvec2!(-0.5, -0.5), qvec2!(0.5, 0.5)));
+    /// let boxed = world.add_body(QBody::new(crate_box, qvec2!(3, 2), q64!(2)));
+    ///
+    /// let mut replay = world.clone();
+    /// for _ in 0..240 {
+    ///     world.step(q64!(1) / 60);
+    /// }
+    ///
+    /// // Both come to rest on the ground.
+    /// assert!((world.body(ball).position.y - q64!(0.5)).abs() < q64!(0.02));
+    /// assert!((world.body(boxed).position.y - q64!(0.5)).abs() < q64!(0.02));
+    /// assert!(world.body(ball).velocity.length() < q64!(0.1));
+    ///
+    /// // Stepping the same world again gives the same bits.
+    /// for _ in 0..240 {
+    ///     replay.step(q64!(1) / 60);
+    /// }
+    /// assert_eq!(replay, world);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `dt` is not positive.
+    ///
+    /// SAT
+    pub fn step(&mut self, dt: Q64) {
+        assert!(dt > Q64::ZERO, "[QWorld::step] Expected dt > 0.");
+
+        for body in self.bodies.iter_mut().filter(|b| !b.is_static()) {
+            let acceleration = self.gravity.saturating_add(body.force.saturating_mul_num(body.inv_mass));
+            body.velocity = body.velocity.saturating_add(acceleration.saturating_mul_num(dt));
+            let angular_acceleration = body.torque.saturating_mul(body.inv_inertia);
+            body.angular_velocity = body.angular_velocity.saturating_add(angular_acceleration.saturating_mul(dt));
+        }
+
+        let mut rows: Vec<Row> = self.joints.iter().flat_map(|joint| joint.rows(&self.bodies, dt)).collect();
+        let mut contacts = self.find_contacts(dt);
+        for _ in 0..self.iterations {
+            for row in &mut rows {
+                solve_row(row, &mut self.bodies);
+            }
+            for contact in &mut contacts {
+                self.solve_contact(contact);
+            }
+        }
+
+        for body in self.bodies.iter_mut() {
+            if !body.is_static() {
+                body.position = body.position.saturating_add(body.velocity.saturating_mul_num(dt));
+                body.angle = body.angle.saturating_add(body.angular_velocity.saturating_mul(dt));
+            }
+            body.force = QVec2::ZERO;
+            body.torque = Q64::ZERO;
+        }
+    }
+
+    /// Collides every pair of bodies with at least one dynamic body and no joint, and prepares the
+    /// contacts.
+    ///
+    /// SAT
+    fn find_contacts(&self, dt: Q64) -> Vec<Contact> {
+        let shapes: Vec<QConvex> = self.bodies.iter().map(QBody::world_shape).collect();
+        let aabbs: Vec<QAabb2> = shapes.iter().map(shape_aabb).collect();
+        let jointed: BTreeSet<(usize, usize)> = self
+            .joints
+            .iter()
+            .map(|joint| {
+                let (x, y) = joint.bodies();
+                (x.min(y), x.max(y))
+            })
+            .collect();
+        let mut contacts = Vec::new();
+
+        for a in 0..self.bodies.len() {
+            for b in a + 1..self.bodies.len() {
+                let (body_a, body_b) = (&self.bodies[a], &self.bodies[b]);
+                if (body_a.is_static() && body_b.is_static()) || !aabbs[a].intersects(&aabbs[b]) {
+                    continue;
+                }
+                if jointed.contains(&(a, b)) {
+                    continue;
+                }
+                if let Some(manifold) = collide(&shapes[a], &shapes[b]) {
+                    contacts.push(self.prepare_contact(a, b, &manifold, dt));
+                }
+            }
+        }
+        contacts
+    }
+
+    /// SAT
+    fn prepare_contact(&self, a: usize, b: usize, manifold: &QManifold, dt: Q64) -> Contact {
+        let (body_a, body_b) = (&self.bodies[a], &self.bodies[b]);
+        let normal = manifold.normal;
+        let tangent = -normal.perp();
+        let restitution = body_a.restitution.max(body_b.restitution);
+        let effective_mass = |r_a: QVec2, r_b: QVec2, axis: QVec2| {
+            let (ca, cb) = (r_a.cross(axis), r_b.cross(axis));
+            let k = body_a
+                .inv_mass
+                .saturating_add(body_b.inv_mass)
+                .saturating_add(body_a.inv_inertia.saturating_mul(ca.saturating_mul(ca)))
+                .saturating_add(body_b.inv_inertia.saturating_mul(cb.saturating_mul(cb)));
+            if k > Q64::ZERO { k.saturating_recip() } else { Q64::ZERO }
+        };
+
+        let points = manifold
+            .contacts()
+            .iter()
+            .map(|contact| {
+                let r_a = contact.point.saturating_sub(body_a.position);
+                let r_b = contact.point.saturating_sub(body_b.position);
+                let approach = body_b.velocity_at(contact.point).saturating_sub(body_a.velocity_at(contact.point)).dot(normal);
+
+                // Pushes apart by a fraction of the penetration, or bounces, whichever is faster.
+                let correction = BAUMGARTE.saturating_mul((contact.depth - LINEAR_SLOP).max(Q64::ZERO)).saturating_div(dt);
+                let bounce = if approach < -RESTITUTION_THRESHOLD { -restitution.saturating_mul(approach) } else { Q64::ZERO };
+
+                ContactPoint {
+                    r_a,
+                    r_b,
+                    normal_mass: effective_mass(r_a, r_b, normal),
+                    tangent_mass: effective_mass(r_a, r_b, tangent),
+                    bias: correction.max(bounce),
+                    normal_impulse: Q64::ZERO,
+                    tangent_impulse: Q64::ZERO,
+                }
+            })
+            .collect();
+
+        Contact {
+            a,
+            b,
+            normal,
+            friction: body_a.friction.saturating_mul(body_b.friction).sqrt(),
+            points,
+        }
+    }
+
+    /// Applies one iteration of sequential impulses to a contact.
+    ///
+    /// SAT
+    fn solve_contact(&mut self, contact: &mut Contact) {
+        let normal = contact.normal;
+        let tangent = -normal.perp();
+        for point in &mut contact.points {
+            // Non-penetration, with the accumulated impulse kept non-negative.
+            let relative = self.relative_velocity(contact.a, contact.b, point.r_a, point.r_b);
+            let impulse = point.bias.saturating_sub(relative.dot(normal)).saturating_mul(point.normal_mass);
+            let total = point.normal_impulse.saturating_add(impulse).max(Q64::ZERO);
+            let impulse = total - point.normal_impulse;
+            point.normal_impulse = total;
+            self.apply_contact_impulse(contact.a, contact.b, point.r_a, point.r_b, normal.saturating_mul_num(impulse));
+
+            // Friction, bounded by the normal impulse.
+            let relative = self.relative_velocity(contact.a, contact.b, point.r_a, point.r_b);
+            let impulse = (-relative.dot(tangent)).saturating_mul(point.tangent_mass);
+            let limit = contact.friction.saturating_mul(point.normal_impulse);
+            let total = point.tangent_impulse.saturating_add(impulse).clamp(-limit, limit);
+            let impulse = total - point.tangent_impulse;
+            point.tangent_impulse = total;
+            self.apply_contact_impulse(contact.a, contact.b, point.r_a, point.r_b, tangent.saturating_mul_num(impulse));
+        }
+    }
+
+    /// Returns the velocity of `b` relative to `a` at the contact offsets.
+    ///
+    /// SAT
+    fn relative_velocity(&self, a: usize, b: usize, r_a: QVec2, r_b: QVec2) -> QVec2 {
+        let (body_a, body_b) = (&self.bodies[a], &self.bodies[b]);
+        let velocity_a = body_a.velocity.saturating_add(r_a.perp().saturating_mul_num(body_a.angular_velocity));
+        let velocity_b = body_b.velocity.saturating_add(r_b.perp().saturating_mul_num(body_b.angular_velocity));
+        velocity_b.saturating_sub(velocity_a)
+    }
+
+    /// Applies `impulse` to `b` and its opposite to `a`.
+    ///
+    /// SAT
+    fn apply_contact_impulse(&mut self, a: usize, b: usize, r_a: QVec2, r_b: QVec2, impulse: QVec2) {
+        let body_a = &mut self.bodies[a];
+        body_a.velocity = body_a.velocity.saturating_sub(impulse.saturating_mul_num(body_a.inv_mass));
+        body_a.angular_velocity = body_a.angular_velocity.saturating_sub(body_a.inv_inertia.saturating_mul(r_a.cross(impulse)));
+        let body_b = &mut self.bodies[b];
+        body_b.velocity = body_b.velocity.saturating_add(impulse.saturating_mul_num(body_b.inv_mass));
+        body_b.angular_velocity = body_b.angular_velocity.saturating_add(body_b.inv_inertia.saturating_mul(r_b.cross(impulse)));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::prelude::*;
+use crate::polygon::QPolygon;
+use crate::rng::QRng;
+use crate::vec2::QVec2;
+
+/// Candidates tried around each active point before it is retired.
+const DEFAULT_ATTEMPTS: u32 = 30;
+
+/// Random points tried to find the first point inside the region.
+const SEED_ATTEMPTS: u32 = 1000;
+
+/// Poisson-disk sampling.
+///
+/// Bridson's algorithm fills a region with random points no closer than a minimum distance to
+/// each other. Active points are picked with [`QRng::next_below`] and candidates are drawn with
+/// [`QRng::in_annulus`], so a given seed produces the same point set on every platform.
+impl QRng {
+    /// Fills the rectangle `[min, max)` with points at least `radius` apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let (min, max) = (qvec2!(0, 0), qvec2!(20, 10));
+    /// let points = QRng::from_seed(9u32).poisson_disk_rect(min, max, q64!(1.5));
+    /// assert!(points.len() > 40);
+    /// for (i, a) in points.iter().enumerate() {
+    ///     assert!(a.x >= min.x && a.x < max.x && a.y >= min.y && a.y < max.y);
+    ///     for b in &points[i + 1..] {
+    ///         assert!(a.distance_squared(*b) >= q64!(1.5) * q64!(1.5));
+    ///     }
+    /// }
+    /// assert_eq!(points, QRng::from_seed(9u32).poisson_disk_rect(min, max, q64!(1.5)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `radius` is not positive or `min` is greater than `max`.
+    pub fn poisson_disk_rect(&mut self, min: QVec2, max: QVec2, radius: Q64) -> Vec<QVec2> {
+        self.poisson_disk_with(min, max, radius, DEFAULT_ATTEMPTS, |_| true)
+    }
+
+    /// Fills the simple polygon `polygon` with points at least `radius` apart.
+    ///
+    /// The polygon may be given in either winding order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let triangle = [qvec2!(0, 0), qvec2!(20, 0), qvec2!(0, 20)];
+    /// let points = QRng::from_seed(9u32).poisson_disk_polygon(&triangle, q64!(2));
+    /// assert!(points.len() > 20);
+    /// assert!(points.iter().all(|p| p.x >= 0 && p.y >= 0 && p.x + p.y <= 20));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `radius` is not positive or `polygon` has fewer than 3 vertices.
+    pub fn poisson_disk_polygon(&mut self, polygon: &[QVec2], radius: Q64) -> Vec<QVec2> {
+        assert!(polygon.len() >= 3, "[QRng::poisson_disk_polygon] Expected at least 3 vertices.");
+        let polygon = QPolygon::new(polygon.to_vec());
+        let aabb = polygon.aabb();
+        self.poisson_disk_with(aabb.min, aabb.max, radius, DEFAULT_ATTEMPTS, |p| polygon.contains_point(p))
+    }
+
+    /// Fills the part of the rectangle `[min, max)` for which `inside` returns `true` with points
+    /// at least `radius` apart, trying `attempts` candidates around each point.
+    ///
+    /// Returns an empty vector if no point inside the region is found.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `radius` is not positive, `attempts` is zero or `min` is greater than `max`.
+    ///
+    /// SAT
+    pub fn poisson_disk_with<F>(&mut self, min: QVec2, max: QVec2, radius: Q64, attempts: u32, inside: F) -> Vec<QVec2>
+    where
+        F: Fn(QVec2) -> bool,
+    {
+        assert!(radius > Q64::ZERO, "[QRng::poisson_disk_with] Expected radius > 0.");
+        assert!(attempts > 0, "[QRng::poisson_disk_with] Expected attempts > 0.");
+        assert!(min.x <= max.x && min.y <= max.y, "[QRng::poisson_disk_with] Expected min <= max.");
+
+        // Each cell holds at most one point.
+        let cell = radius * Q64::FRAC_1_SQRT_2;
+        let size = max.saturating_sub(min);
+        let cols = size.x.saturating_div(cell).ceil().to_num::<usize>().max(1);
+        let rows = size.y.saturating_div(cell).ceil().to_num::<usize>().max(1);
+        let cell_of = |p: QVec2| {
+            let offset = p.saturating_sub(min).saturating_div_num(cell);
+            let col = offset.x.to_num::<usize>().min(cols - 1);
+            let row = offset.y.to_num::<usize>().min(rows - 1);
+            (col, row)
+        };
+
+        let mut grid: Vec<Option<usize>> = vec![None; cols * rows];
+        let mut points: Vec<QVec2> = Vec::new();
+        let mut active: Vec<usize> = Vec::new();
+        let radius_squared = radius.saturating_mul(radius);
+        let in_region = |p: QVec2| p.x >= min.x && p.x < max.x && p.y >= min.y && p.y < max.y && inside(p);
+
+        let Some(first) = (0..SEED_ATTEMPTS).map(|_| self.in_rect(min, max)).find(|p| in_region(*p)) else {
+            return points;
+        };
+        let (col, row) = cell_of(first);
+        grid[row * cols + col] = Some(0);
+        points.push(first);
+        active.push(0);
+
+        while !active.is_empty() {
+            let slot = self.next_below(active.len() as u32) as usize;
+            let center = points[active[slot]];
+            let mut found = false;
+
+            for _ in 0..attempts {
+                let candidate = center.saturating_add(self.in_annulus(radius, radius.saturating_mul(Q64::TWO)));
+                if !in_region(candidate) {
+                    continue;
+                }
+
+                let (col, row) = cell_of(candidate);
+                let too_close = (row.saturating_sub(2)..(row + 3).min(rows)).any(|r| {
+                    (col.saturating_sub(2)..(col + 3).min(cols)).any(|c| {
+                        grid[r * cols + c].is_some_and(|i| points[i].distance_squared(candidate) < radius_squared)
+                    })
+                });
+                if too_close {
+                    continue;
+                }
+
+                grid[row * cols + col] = Some(points.len());
+                active.push(points.len());
+                points.push(candidate);
+                found = true;
+                break;
+            }
+
+            if !found {
+                active.swap_remove(slot);
+            }
+        }
+
+        points
+    }
+}
@@ -0,0 +1,389 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::predicates::orient2d;
+use crate::ray::QSegment2;
+use crate::shapes::QObb2;
+use crate::vec2::QVec2;
+use crate::wide::I320;
+use core::cmp::Ordering;
+use serde::{Deserialize, Serialize};
+
+/// The winding order of a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QWinding {
+    /// Positive signed area.
+    CounterClockwise,
+    /// Negative signed area.
+    Clockwise,
+    /// Zero signed area.
+    Degenerate,
+}
+
+/// A simple polygon given by its vertices, without repeating the first vertex at the end.
+///
+/// Area, centroid, winding, convexity and containment are computed exactly on the raw bits of
+/// the coordinates with wide integers, so they don't suffer from the saturation of
+/// [`QVec2::cross`] for large coordinates.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawPolygon")]
+pub struct QPolygon {
+    vertices: Vec<QVec2>,
+}
+
+/// The serialized form of a [`QPolygon`], checked before it becomes one.
+#[derive(Deserialize)]
+struct RawPolygon {
+    vertices: Vec<QVec2>,
+}
+
+impl TryFrom<RawPolygon> for QPolygon {
+    type Error = &'static str;
+
+    fn try_from(raw: RawPolygon) -> Result<Self, Self::Error> {
+        if raw.vertices.len() < 3 {
+            return Err("[QPolygon::deserialize] Expected at least 3 vertices.");
+        }
+        Ok(Self { vertices: raw.vertices })
+    }
+}
+
+impl QPolygon {
+    /// Creates a new polygon.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if there are fewer than 3 vertices.
+    #[inline]
+    #[must_use]
+    pub fn new(vertices: Vec<QVec2>) -> Self {
+        assert!(vertices.len() >= 3, "[QPolygon::new] Expected at least 3 vertices.");
+        Self { vertices }
+    }
+
+    /// Returns the vertices, in order.
+    #[inline]
+    #[must_use]
+    pub fn vertices(&self) -> &[QVec2] {
+        &self.vertices
+    }
+
+    /// Computes the convex hull of `points` with Andrew's monotone chain algorithm.
+    ///
+    /// The hull is returned counter-clockwise, starting from the point with the smallest `x`
+    /// (then smallest `y`). Duplicate points are merged, and points lying on a hull edge are
+    /// dropped so that every returned vertex is a strict corner. If all points are collinear the
+    /// two extreme points are returned, and a single distinct point is returned alone. Turns are
+    /// decided with an exact orientation test, so the result is the same on every platform
+    /// whatever the magnitude of the coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::polygon::QPolygon;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let points = [
+    ///     qvec2!(1, 1), qvec2!(0, 0), qvec2!(2, 0), qvec2!(1, 0), qvec2!(2, 2),
+    ///     qvec2!(0, 2), qvec2!(0, 0), qvec2!(1, 2), qvec2!(0.5, 1.5),
+    /// ];
+    /// assert_eq!(QPolygon::convex_hull(&points), vec![qvec2!(0, 0), qvec2!(2, 0), qvec2!(2, 2), qvec2!(0, 2)]);
+    ///
+    /// let collinear = [qvec2!(3, 3), qvec2!(1, 1), qvec2!(2, 2), qvec2!(1, 1)];
+    /// assert_eq!(QPolygon::convex_hull(&collinear), vec![qvec2!(1, 1), qvec2!(3, 3)]);
+    ///
+    /// // Coordinates large enough for `QVec2::cross` to saturate.
+    /// let (min, max) = (qvec2!(-2000000000, -2000000000), qvec2!(2000000000, -2000000000));
+    /// let big = [min, max, qvec2!(0, 0), qvec2!(0, 2000000000), qvec2!(1, 1999999999)];
+    /// assert_eq!(QPolygon::convex_hull(&big), vec![min, max, qvec2!(1, 1999999999), qvec2!(0, 2000000000)]);
+    /// ```
+    #[must_use]
+    pub fn convex_hull(points: &[QVec2]) -> Vec<QVec2> {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a.x.cmp(&b.x).then(a.y.cmp(&b.y)));
+        sorted.dedup();
+        if sorted.len() < 3 {
+            return sorted;
+        }
+
+        let mut hull: Vec<QVec2> = Vec::with_capacity(sorted.len() + 1);
+        // Lower chain from left to right.
+        for &point in &sorted {
+            while hull.len() >= 2 && orient2d(hull[hull.len() - 2], hull[hull.len() - 1], point) != Ordering::Greater {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // Upper chain from right to left.
+        let lower_len = hull.len() + 1;
+        for &point in sorted.iter().rev().skip(1) {
+            while hull.len() >= lower_len && orient2d(hull[hull.len() - 2], hull[hull.len() - 1], point) != Ordering::Greater {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The upper chain ends on the first point.
+        hull.pop();
+        hull
+    }
+
+    /// Returns the edges of the polygon, from each vertex to the next.
+    pub fn edges(&self) -> impl Iterator<Item = QSegment2> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| QSegment2::new(self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Returns twice the signed area in raw units, i.e. scaled by `2^64`.
+    fn twice_area_raw(&self) -> I320 {
+        self.edges().fold(I320::ZERO, |acc, edge| acc + cross_raw(edge.a, edge.b))
+    }
+
+    /// Returns the signed area, positive for counter-clockwise polygons.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::polygon::QPolygon;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let square = QPolygon::new(vec![qvec2!(0, 0), qvec2!(2, 0), qvec2!(2, 2), qvec2!(0, 2)]);
+    /// assert_eq!(square.signed_area(), q64!(4));
+    ///
+    /// // `QVec2::cross` would saturate here.
+    /// let huge = QPolygon::new(vec![qvec2!(-40000, -40000), qvec2!(-39999, -40000), qvec2!(-40000, -39999)]);
+    /// assert_eq!(huge.signed_area(), Q64::HALF);
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn signed_area(&self) -> Q64 {
+        self.twice_area_raw().div_round(I320::from_i128(1 << 33)).saturating_to_q64()
+    }
+
+    /// Returns the area.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn area(&self) -> Q64 {
+        self.signed_area().saturating_abs()
+    }
+
+    /// Returns the winding order, decided exactly even when the area rounds to zero.
+    #[must_use]
+    pub fn winding(&self) -> QWinding {
+        match self.twice_area_raw().signum() {
+            Ordering::Greater => QWinding::CounterClockwise,
+            Ordering::Less => QWinding::Clockwise,
+            Ordering::Equal => QWinding::Degenerate,
+        }
+    }
+
+    /// Reverses the order of the vertices, flipping the winding.
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.vertices.reverse();
+    }
+
+    /// Reorders the vertices counter-clockwise if they are clockwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::polygon::{QPolygon, QWinding};
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut triangle = QPolygon::new(vec![qvec2!(0, 0), qvec2!(0, 1), qvec2!(1, 0)]);
+    /// assert_eq!(triangle.winding(), QWinding::Clockwise);
+    /// triangle.make_ccw();
+    /// assert_eq!(triangle.winding(), QWinding::CounterClockwise);
+    /// ```
+    pub fn make_ccw(&mut self) {
+        if self.winding() == QWinding::Clockwise {
+            self.reverse();
+        }
+    }
+
+    /// Reorders the vertices clockwise if they are counter-clockwise.
+    pub fn make_cw(&mut self) {
+        if self.winding() == QWinding::CounterClockwise {
+            self.reverse();
+        }
+    }
+
+    /// Returns the centroid of the area, or the average of the vertices if the area is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::polygon::QPolygon;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let l_shape = QPolygon::new(vec![
+    ///     qvec2!(0, 0), qvec2!(2, 0), qvec2!(2, 1), qvec2!(1, 1), qvec2!(1, 2), qvec2!(0, 2),
+    /// ]);
+    /// assert!(l_shape.centroid().abs_diff_eq(qvec2!(5, 5) / q64!(6), Q64::DELTA));
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn centroid(&self) -> QVec2 {
+        let twice_area = self.twice_area_raw();
+        if twice_area == I320::ZERO {
+            let n = I320::from_i128(self.vertices.len() as i128);
+            let sum_x = self.vertices.iter().fold(I320::ZERO, |acc, v| acc + I320::from_bits(v.x));
+            let sum_y = self.vertices.iter().fold(I320::ZERO, |acc, v| acc + I320::from_bits(v.y));
+            return QVec2::new(sum_x.div_round(n).saturating_to_q64(), sum_y.div_round(n).saturating_to_q64());
+        }
+
+        let mut sum_x = I320::ZERO;
+        let mut sum_y = I320::ZERO;
+        for edge in self.edges() {
+            let cross = cross_raw(edge.a, edge.b);
+            sum_x = sum_x + (I320::from_bits(edge.a.x) + I320::from_bits(edge.b.x)) * cross;
+            sum_y = sum_y + (I320::from_bits(edge.a.y) + I320::from_bits(edge.b.y)) * cross;
+        }
+        let divisor = twice_area * I320::from_i128(3);
+        QVec2::new(
+            sum_x.div_round(divisor).saturating_to_q64(),
+            sum_y.div_round(divisor).saturating_to_q64(),
+        )
+    }
+
+    /// Returns the sum of the lengths of the edges.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn perimeter(&self) -> Q64 {
+        self.edges().fold(Q64::ZERO, |acc, edge| acc.saturating_add(edge.length()))
+    }
+
+    /// Returns the bounding box of the polygon.
+    #[inline]
+    #[must_use]
+    pub fn aabb(&self) -> QAabb2 {
+        QAabb2::from_points(&self.vertices)
+    }
+
+    /// Returns whether the polygon is convex, in either winding.
+    ///
+    /// Collinear vertices are allowed, but a polygon whose vertices are all collinear is not
+    /// convex.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::polygon::QPolygon;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let square = QPolygon::new(vec![qvec2!(0, 0), qvec2!(1, 0), qvec2!(2, 0), qvec2!(2, 2), qvec2!(0, 2)]);
+    /// assert!(square.is_convex());
+    /// let arrow = QPolygon::new(vec![qvec2!(0, 0), qvec2!(2, 1), qvec2!(0, 2), qvec2!(1, 1)]);
+    /// assert!(!arrow.is_convex());
+    /// ```
+    #[must_use]
+    pub fn is_convex(&self) -> bool {
+        let n = self.vertices.len();
+        let mut turn = Ordering::Equal;
+        // Self-intersecting polygons with consistent turns wind more than once, which shows up
+        // as more than two sign changes of the edge directions along each axis.
+        let mut x_flips = 0;
+        let mut y_flips = 0;
+        let mut prev_dx = Ordering::Equal;
+        let mut prev_dy = Ordering::Equal;
+        for i in 0..n {
+            let a = self.vertices[i];
+            let b = self.vertices[(i + 1) % n];
+            let c = self.vertices[(i + 2) % n];
+
+            let current = orient2d(a, b, c);
+            if current != Ordering::Equal {
+                if turn != Ordering::Equal && turn != current {
+                    return false;
+                }
+                turn = current;
+            }
+
+            for (prev, curr, flips) in [(&mut prev_dx, b.x.cmp(&a.x), &mut x_flips), (&mut prev_dy, b.y.cmp(&a.y), &mut y_flips)] {
+                if curr != Ordering::Equal {
+                    if *prev != Ordering::Equal && *prev != curr {
+                        *flips += 1;
+                    }
+                    *prev = curr;
+                }
+            }
+        }
+        turn != Ordering::Equal && x_flips <= 2 && y_flips <= 2
+    }
+
+    /// Returns whether `point` is inside the polygon or on its boundary.
+    ///
+    /// Uses the non-zero winding number rule with exact orientation tests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::polygon::QPolygon;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let l_shape = QPolygon::new(vec![
+    ///     qvec2!(0, 0), qvec2!(2, 0), qvec2!(2, 1), qvec2!(1, 1), qvec2!(1, 2), qvec2!(0, 2),
+    /// ]);
+    /// assert!(l_shape.contains_point(qvec2!(0.5, 1.5)));
+    /// assert!(l_shape.contains_point(qvec2!(1, 1.5)));
+    /// assert!(!l_shape.contains_point(qvec2!(1.5, 1.5)));
+    /// ```
+    #[must_use]
+    pub fn contains_point(&self, point: QVec2) -> bool {
+        let mut winding_number = 0_i64;
+        for edge in self.edges() {
+            let (a, b) = (edge.a, edge.b);
+            let side = orient2d(a, b, point);
+            if side == Ordering::Equal && point.x >= a.x.min(b.x) && point.x <= a.x.max(b.x)
+                && point.y >= a.y.min(b.y) && point.y <= a.y.max(b.y) {
+                return true;
+            }
+            if a.y <= point.y {
+                if b.y > point.y && side == Ordering::Greater {
+                    winding_number += 1;
+                }
+            } else if b.y <= point.y && side == Ordering::Less {
+                winding_number -= 1;
+            }
+        }
+        winding_number != 0
+    }
+}
+
+/// Returns `a.cross(b)` on the raw bits, exactly.
+fn cross_raw(a: QVec2, b: QVec2) -> I320 {
+    I320::from_bits(a.x) * I320::from_bits(b.y) - I320::from_bits(a.y) * I320::from_bits(b.x)
+}
+
+impl From<Vec<QVec2>> for QPolygon {
+    fn from(vertices: Vec<QVec2>) -> Self {
+        Self::new(vertices)
+    }
+}
+
+impl From<QAabb2> for QPolygon {
+    fn from(aabb: QAabb2) -> Self {
+        Self::new(vec![
+            aabb.min,
+            QVec2::new(aabb.max.x, aabb.min.y),
+            aabb.max,
+            QVec2::new(aabb.min.x, aabb.max.y),
+        ])
+    }
+}
+
+impl From<QObb2> for QPolygon {
+    fn from(obb: QObb2) -> Self {
+        Self::new(obb.corners().to_vec())
+    }
+}
@@ -0,0 +1,88 @@
+use crate::vec2::QVec2;
+use crate::wide::I320;
+use core::cmp::Ordering;
+
+/// Returns the orientation of `c` relative to the directed line from `a` to `b`.
+///
+/// `Greater` if `a`, `b`, `c` make a counter-clockwise turn, `Less` if clockwise and `Equal` if
+/// they are collinear.
+///
+/// Unlike a test on [`QVec2::cross`], which saturates for large coordinates, the determinant is
+/// computed exactly on the raw bits in a 320-bit integer, so the result is right for every input.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::predicates::orient2d;
+/// use qmath::vec2::QVec2;
+/// use core::cmp::Ordering;
+///
+/// assert_eq!(orient2d(qvec2!(0, 0), qvec2!(1, 0), qvec2!(0, 1)), Ordering::Greater);
+/// assert_eq!(orient2d(qvec2!(0, 0), qvec2!(0, 1), qvec2!(1, 0)), Ordering::Less);
+/// assert_eq!(orient2d(qvec2!(0, 0), qvec2!(1, 1), qvec2!(3, 3)), Ordering::Equal);
+///
+/// // The cross product saturates, the predicate doesn't.
+/// let (a, b, c) = (QVec2::MIN, QVec2::MAX, qvec2!(-2147483648, 2147483647));
+/// assert_eq!(orient2d(a, b, c), Ordering::Greater);
+/// assert_eq!(orient2d(a, b, QVec2::new(Q64::MAX, Q64::MAX - Q64::DELTA)), Ordering::Less);
+/// ```
+#[must_use]
+pub fn orient2d(a: QVec2, b: QVec2, c: QVec2) -> Ordering {
+    let (abx, aby) = delta(b, a);
+    let (acx, acy) = delta(c, a);
+    (abx * acy - aby * acx).signum()
+}
+
+/// Returns whether `d` is inside the circle through `a`, `b` and `c`.
+///
+/// `Greater` if `d` is strictly inside, `Less` if strictly outside and `Equal` if the four points
+/// are cocircular, provided `a`, `b`, `c` are counter-clockwise. The result is negated for a
+/// clockwise triangle.
+///
+/// The determinant needs about 260 bits and is computed exactly on the raw bits in a 320-bit
+/// integer.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::predicates::incircle;
+/// use qmath::vec2::QVec2;
+/// use core::cmp::Ordering;
+///
+/// let (a, b, c) = (qvec2!(-1, 0), qvec2!(1, 0), qvec2!(0, 1));
+/// assert_eq!(incircle(a, b, c, qvec2!(0, 0)), Ordering::Greater);
+/// assert_eq!(incircle(a, b, c, qvec2!(0, -1)), Ordering::Equal);
+/// assert_eq!(incircle(a, b, c, qvec2!(0, -1) - QVec2::DELTA_Y), Ordering::Less);
+/// assert_eq!(incircle(a, c, b, qvec2!(0, 0)), Ordering::Less);
+///
+/// // Coordinates for which every intermediate product would overflow 128 bits.
+/// let scale = Q64::MAX;
+/// let (a, b, c) = (QVec2::new(-scale, Q64::ZERO), QVec2::new(scale, Q64::ZERO), QVec2::new(Q64::ZERO, scale));
+/// assert_eq!(incircle(a, b, c, qvec2!(0, 0)), Ordering::Greater);
+/// assert_eq!(incircle(a, b, c, QVec2::new(Q64::ZERO, Q64::MIN)), Ordering::Less);
+/// ```
+#[must_use]
+pub fn incircle(a: QVec2, b: QVec2, c: QVec2, d: QVec2) -> Ordering {
+    let (adx, ady) = delta(a, d);
+    let (bdx, bdy) = delta(b, d);
+    let (cdx, cdy) = delta(c, d);
+
+    let a_lift = adx * adx + ady * ady;
+    let b_lift = bdx * bdx + bdy * bdy;
+    let c_lift = cdx * cdx + cdy * cdy;
+
+    let det = a_lift * (bdx * cdy - bdy * cdx)
+        + b_lift * (cdx * ady - cdy * adx)
+        + c_lift * (adx * bdy - ady * bdx);
+    det.signum()
+}
+
+/// Returns `p - q` on the raw bits, exactly.
+fn delta(p: QVec2, q: QVec2) -> (I320, I320) {
+    (
+        I320::from_bits(p.x) - I320::from_bits(q.x),
+        I320::from_bits(p.y) - I320::from_bits(q.y),
+    )
+}
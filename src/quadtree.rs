@@ -0,0 +1,367 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+
+/// A handle to an item of a [`QQuadTree`].
+///
+/// Keys of removed items are never handed out again by the same tree, so a stale key is simply
+/// not found. An item slot is only recycled while its generation can still grow.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct QQuadTreeKey {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    /// Stale keys carry an older generation. Stuck at `u32::MAX`, the slot is abandoned.
+    generation: u32,
+    /// The box, the value and the node holding the item.
+    item: Option<(QAabb2, T, usize)>,
+}
+
+#[derive(Debug, Clone)]
+struct QuadNode {
+    bounds: QAabb2,
+    depth: u32,
+    /// Index of the first of four consecutive children, bottom-left, bottom-right, top-left and
+    /// top-right.
+    children: Option<usize>,
+    items: Vec<QQuadTreeKey>,
+}
+
+/// A region quadtree of points and boxes.
+///
+/// Each item lives in the deepest node whose quadrant contains its whole box, so boxes straddling
+/// a split stay higher up. A node splits once it holds more than `capacity` items, unless it is
+/// at `max_depth`. Items outside the bounds of the tree are kept at the root.
+///
+/// Queries visit the quadrants bottom-left, bottom-right, top-left, top-right, depth first, and
+/// the items of a node in insertion order, so results don't depend on anything but the sequence
+/// of operations.
+#[derive(Debug, Clone)]
+pub struct QQuadTree<T> {
+    nodes: Vec<QuadNode>,
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    max_depth: u32,
+    capacity: usize,
+    len: usize,
+}
+
+impl<T> QQuadTree<T> {
+    /// Creates an empty quadtree covering `bounds`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `capacity` is zero.
+    #[must_use]
+    pub fn new(bounds: QAabb2, max_depth: u32, capacity: usize) -> Self {
+        assert!(capacity > 0, "[QQuadTree::new] Expected capacity > 0.");
+        Self {
+            nodes: vec![QuadNode { bounds, depth: 0, children: None, items: Vec::new() }],
+            slots: Vec::new(),
+            free: Vec::new(),
+            max_depth,
+            capacity,
+            len: 0,
+        }
+    }
+
+    /// Returns the bounds of the tree.
+    #[inline]
+    #[must_use]
+    pub fn bounds(&self) -> QAabb2 {
+        self.nodes[0].bounds
+    }
+
+    /// Returns the number of items.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there are no items.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `value` at the point `position` and returns its key.
+    pub fn insert_point(&mut self, position: QVec2, value: T) -> QQuadTreeKey {
+        self.insert(QAabb2::new(position, position), value)
+    }
+
+    /// Inserts `value` covering `aabb` and returns its key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::quadtree::QQuadTree;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut tree = QQuadTree::new(QAabb2::new(qvec2!(0, 0), qvec2!(64, 64)), 6, 2);
+    /// let tower = tree.insert_point(qvec2!(10, 10), "tower");
+    /// let wall = tree.insert(QAabb2::new(qvec2!(20, 30), qvec2!(40, 34)), "wall");
+    /// let scout = tree.insert_point(qvec2!(50, 50), "scout");
+    ///
+    /// let seen: Vec<_> = tree.query_circle(qvec2!(12, 14), q64!(5)).map(|(_, v)| *v).collect();
+    /// assert_eq!(seen, ["tower"]);
+    /// let selected: Vec<_> = tree.query_rect(QAabb2::new(qvec2!(30, 30), qvec2!(60, 60))).map(|(k, _)| k).collect();
+    /// assert_eq!(selected, [wall, scout]);
+    /// assert_eq!(tree.nearest(qvec2!(48, 46)).map(|(k, _)| k), Some(scout));
+    ///
+    /// assert!(tree.update(scout, QAabb2::new(qvec2!(1, 1), qvec2!(1, 1))));
+    /// assert_eq!(tree.nearest(qvec2!(48, 46)).map(|(k, _)| k), Some(wall));
+    /// assert_eq!(tree.remove(tower), Some("tower"));
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn insert(&mut self, aabb: QAabb2, value: T) -> QQuadTreeKey {
+        let key = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.item = Some((aabb, value, 0));
+                QQuadTreeKey { index, generation: slot.generation }
+            }
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("[QQuadTree::insert] Too many items.");
+                self.slots.push(Slot { generation: 0, item: Some((aabb, value, 0)) });
+                QQuadTreeKey { index, generation: 0 }
+            }
+        };
+        self.link(key, aabb);
+        self.len += 1;
+        key
+    }
+
+    /// Removes the item of `key` and returns its value, or `None` if it isn't present.
+    ///
+    /// Nodes emptied by removals are kept for later insertions.
+    pub fn remove(&mut self, key: QQuadTreeKey) -> Option<T> {
+        self.item(key)?;
+        self.unlink(key);
+        let slot = &mut self.slots[key.index as usize];
+        let (_, value, _) = slot.item.take()?;
+        slot.generation += 1;
+        if slot.generation < u32::MAX {
+            self.free.push(key.index);
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Moves the item of `key` to `aabb`, returning `false` if it isn't present.
+    pub fn update(&mut self, key: QQuadTreeKey, aabb: QAabb2) -> bool {
+        if self.item(key).is_none() {
+            return false;
+        }
+        self.unlink(key);
+        if let Some((item_aabb, _, _)) = &mut self.slots[key.index as usize].item {
+            *item_aabb = aabb;
+        }
+        self.link(key, aabb);
+        true
+    }
+
+    /// Removes all items and nodes. Keys handed out before stay invalid.
+    pub fn clear(&mut self) {
+        let bounds = self.bounds();
+        self.nodes = vec![QuadNode { bounds, depth: 0, children: None, items: Vec::new() }];
+        for slot in &mut self.slots {
+            if slot.item.take().is_some() {
+                slot.generation += 1;
+            }
+        }
+        // Hands out the lowest indices first.
+        self.free = (0..self.slots.len() as u32).rev().filter(|&i| self.slots[i as usize].generation < u32::MAX).collect();
+        self.len = 0;
+    }
+
+    /// Returns the box of the item of `key`.
+    #[must_use]
+    pub fn aabb(&self, key: QQuadTreeKey) -> Option<QAabb2> {
+        self.item(key).map(|(aabb, _, _)| *aabb)
+    }
+
+    /// Returns the value of the item of `key`.
+    #[must_use]
+    pub fn get(&self, key: QQuadTreeKey) -> Option<&T> {
+        self.item(key).map(|(_, value, _)| value)
+    }
+
+    /// Returns the value of the item of `key` mutably.
+    #[must_use]
+    pub fn get_mut(&mut self, key: QQuadTreeKey) -> Option<&mut T> {
+        let slot = self.slots.get_mut(key.index as usize)?;
+        if slot.generation != key.generation {
+            return None;
+        }
+        slot.item.as_mut().map(|(_, value, _)| value)
+    }
+
+    /// Iterates over all items by key, in the order of [`QQuadTreeKey`].
+    pub fn iter(&self) -> impl Iterator<Item = (QQuadTreeKey, QAabb2, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let (aabb, value, _) = slot.item.as_ref()?;
+            Some((QQuadTreeKey { index: index as u32, generation: slot.generation }, *aabb, value))
+        })
+    }
+
+    /// Iterates over the items whose box intersects `rect`, boundary included.
+    pub fn query_rect(&self, rect: QAabb2) -> impl Iterator<Item = (QQuadTreeKey, &T)> + '_ {
+        self.query_with(move |bounds| bounds.intersects(&rect))
+    }
+
+    /// Iterates over the items whose box is within `radius` of `center`, boundary included.
+    ///
+    /// SAT
+    pub fn query_circle(&self, center: QVec2, radius: Q64) -> impl Iterator<Item = (QQuadTreeKey, &T)> + '_ {
+        let radius_squared = radius.saturating_mul(radius);
+        self.query_with(move |bounds| bounds.distance_squared_to_point(center) <= radius_squared)
+    }
+
+    /// Returns the item whose box is closest to `point`, preferring the lowest key on ties.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn nearest(&self, point: QVec2) -> Option<(QQuadTreeKey, &T)> {
+        let mut best: Option<(Q64, QQuadTreeKey, &T)> = None;
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            // The root also holds the items outside the bounds, so it is never pruned.
+            if index != 0
+                && let Some((distance, _, _)) = best
+                && node.bounds.distance_squared_to_point(point) > distance
+            {
+                continue;
+            }
+
+            for key in &node.items {
+                let Some((aabb, value, _)) = self.item(*key) else {
+                    continue;
+                };
+                let distance = aabb.distance_squared_to_point(point);
+                if best.is_none_or(|(d, k, _)| distance < d || (distance == d && *key < k)) {
+                    best = Some((distance, *key, value));
+                }
+            }
+
+            if let Some(first) = node.children {
+                // Pushes the farthest quadrant first, so the nearest is searched first.
+                let mut children: [usize; 4] = core::array::from_fn(|i| first + i);
+                children.sort_by_key(|child| (self.nodes[*child].bounds.distance_squared_to_point(point), *child));
+                stack.extend(children.iter().rev());
+            }
+        }
+        best.map(|(_, key, value)| (key, value))
+    }
+
+    /// Iterates depth first over the items whose box passes `test`, descending into the nodes
+    /// whose bounds pass it.
+    fn query_with<F>(&self, test: F) -> impl Iterator<Item = (QQuadTreeKey, &T)> + '_
+    where
+        F: Fn(&QAabb2) -> bool + 'static,
+    {
+        let mut stack = vec![0];
+        let mut pending: Vec<QQuadTreeKey> = Vec::new();
+        core::iter::from_fn(move || {
+            loop {
+                while let Some(key) = pending.pop() {
+                    if let Some((aabb, value, _)) = self.item(key)
+                        && test(aabb)
+                    {
+                        return Some((key, value));
+                    }
+                }
+                let index = stack.pop()?;
+                let node = &self.nodes[index];
+                // Items outside the bounds are kept at the root, so its bounds aren't tested.
+                if index != 0 && !test(&node.bounds) {
+                    continue;
+                }
+                pending.extend(node.items.iter().rev());
+                if let Some(first) = node.children {
+                    stack.extend((first..first + 4).rev());
+                }
+            }
+        })
+    }
+
+    fn item(&self, key: QQuadTreeKey) -> Option<&(QAabb2, T, usize)> {
+        let slot = self.slots.get(key.index as usize)?;
+        if slot.generation != key.generation {
+            return None;
+        }
+        slot.item.as_ref()
+    }
+
+    /// Stores `key` in the deepest node containing `aabb`, splitting it if it gets too full.
+    fn link(&mut self, key: QQuadTreeKey, aabb: QAabb2) {
+        let mut index = 0;
+        while let Some(child) = self.child_containing(index, &aabb) {
+            index = child;
+        }
+        self.attach(key, index);
+        self.split_if_full(index);
+    }
+
+    fn unlink(&mut self, key: QQuadTreeKey) {
+        if let Some((_, _, node)) = self.item(key) {
+            let node = *node;
+            self.nodes[node].items.retain(|k| *k != key);
+        }
+    }
+
+    fn attach(&mut self, key: QQuadTreeKey, node: usize) {
+        self.nodes[node].items.push(key);
+        if let Some((_, _, n)) = &mut self.slots[key.index as usize].item {
+            *n = node;
+        }
+    }
+
+    /// Returns the first child of `index` whose bounds contain `aabb`.
+    fn child_containing(&self, index: usize, aabb: &QAabb2) -> Option<usize> {
+        let first = self.nodes[index].children?;
+        (first..first + 4).find(|child| self.nodes[*child].bounds.contains(aabb))
+    }
+
+    /// Splits a full leaf and pushes its items down, recursively.
+    fn split_if_full(&mut self, index: usize) {
+        let node = &self.nodes[index];
+        if node.children.is_some() || node.items.len() <= self.capacity || node.depth >= self.max_depth {
+            return;
+        }
+
+        let QAabb2 { min, max } = node.bounds;
+        let center = node.bounds.center();
+        let depth = node.depth + 1;
+        let first = self.nodes.len();
+        for (min, max) in [
+            (min, center),
+            (QVec2::new(center.x, min.y), QVec2::new(max.x, center.y)),
+            (QVec2::new(min.x, center.y), QVec2::new(center.x, max.y)),
+            (center, max),
+        ] {
+            self.nodes.push(QuadNode { bounds: QAabb2::new(min, max), depth, children: None, items: Vec::new() });
+        }
+        self.nodes[index].children = Some(first);
+
+        let items = core::mem::take(&mut self.nodes[index].items);
+        for key in items {
+            let Some((aabb, _, _)) = self.item(key) else {
+                continue;
+            };
+            let target = self.child_containing(index, aabb).unwrap_or(index);
+            self.attach(key, target);
+        }
+        for child in first..first + 4 {
+            self.split_if_full(child);
+        }
+    }
+}
@@ -0,0 +1,425 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::shapes::{QCapsule2, QCircle, QObb2};
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+
+/// The result of an intersection query.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QRayHit {
+    /// The intersection point.
+    pub point: QVec2,
+    /// The parameter of the intersection point along the querying ray, segment or line.
+    pub t: Q64,
+    /// The unit normal of the hit surface, facing against the query direction.
+    pub normal: QVec2,
+}
+
+/// An infinite line through `point` along `dir`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QLine2 {
+    pub point: QVec2,
+    pub dir: QVec2,
+}
+
+/// A ray starting at `origin` and extending along `dir`.
+///
+/// Points on the ray are `origin + dir * t` for `t >= 0`, so `t` is a distance when `dir` is
+/// normalized.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QRay2 {
+    pub origin: QVec2,
+    pub dir: QVec2,
+}
+
+/// A line segment between `a` and `b`.
+///
+/// Points on the segment are `a + (b - a) * t` for `t` in `[0, 1]`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QSegment2 {
+    pub a: QVec2,
+    pub b: QVec2,
+}
+
+/// Shapes that can be hit by a ray.
+pub trait QRaycast {
+    /// Returns the first intersection of `ray` with the shape for `t` in `[0, max_t]`.
+    ///
+    /// A ray starting inside a solid shape hits it at `t = 0` with the normal opposing `ray.dir`.
+    fn raycast(&self, ray: &QRay2, max_t: Q64) -> Option<QRayHit>;
+}
+
+/// Returns the unit normal of `edge` facing against `dir`.
+fn facing_normal(edge: QVec2, dir: QVec2) -> QVec2 {
+    let normal = edge.perp().normalize();
+    if normal.dot(dir) > Q64::ZERO { -normal } else { normal }
+}
+
+/// Returns the unit vector opposing `dir`, used for hits at `t = 0` from inside a shape.
+fn inside_normal(dir: QVec2) -> QVec2 {
+    -dir.normalize()
+}
+
+/// Solves `p + d * t = q + e * u` for `(t, u)`, or `None` when the directions are parallel.
+///
+/// Directions whose cross product is within `Q64::EPS` of zero are treated as parallel. The
+/// threshold is absolute, so it scales with the lengths of `d` and `e`: with unit directions it
+/// rejects lines closer than about `1e-8` radians to parallel. Parallel and collinear inputs never
+/// intersect.
+///
+/// SAT
+fn solve_lines(p: QVec2, d: QVec2, q: QVec2, e: QVec2) -> Option<(Q64, Q64)> {
+    let denom = d.cross(e);
+    if denom.abs() <= Q64::EPS {
+        return None;
+    }
+    let w = q.saturating_sub(p);
+    Some((w.cross(e).saturating_div(denom), w.cross(d).saturating_div(denom)))
+}
+
+impl QLine2 {
+    /// Creates a new line.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `dir` is zero.
+    #[inline]
+    #[must_use]
+    pub fn new(point: QVec2, dir: QVec2) -> Self {
+        assert!(dir != QVec2::ZERO, "[QLine2::new] Expected a non-zero direction.");
+        Self { point, dir }
+    }
+
+    /// Creates the line through `a` and `b`.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn from_points(a: QVec2, b: QVec2) -> Self {
+        Self::new(a, b.saturating_sub(a))
+    }
+
+    /// Returns the point of the line closest to `point`.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn closest_point(&self, point: QVec2) -> QVec2 {
+        let t = point.saturating_sub(self.point).dot(self.dir).saturating_div(self.dir.length_squared());
+        self.point.saturating_add(self.dir.saturating_mul_num(t))
+    }
+
+    /// Returns the intersection with `other`, with `t` along `self` and the normal of `other`.
+    ///
+    /// Returns `None` for near-parallel lines, see [`QRay2::cast`] for the threshold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::ray::QLine2;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let a = QLine2::new(qvec2!(0, 0), qvec2!(1, 1));
+    /// let b = QLine2::new(qvec2!(4, 0), qvec2!(0, 1));
+    /// let hit = a.intersect_line(&b).unwrap();
+    /// assert_eq!(hit.point, qvec2!(4, 4));
+    /// assert_eq!(hit.t, q64!(4));
+    /// assert_eq!(hit.normal, qvec2!(-1, 0));
+    /// assert!(a.intersect_line(&QLine2::new(qvec2!(0, 1), qvec2!(2, 2))).is_none());
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn intersect_line(&self, other: &Self) -> Option<QRayHit> {
+        let (t, _) = solve_lines(self.point, self.dir, other.point, other.dir)?;
+        Some(QRayHit {
+            point: self.point.saturating_add(self.dir.saturating_mul_num(t)),
+            t,
+            normal: facing_normal(other.dir, self.dir),
+        })
+    }
+}
+
+impl QRay2 {
+    /// Creates a new ray.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `dir` is zero.
+    #[inline]
+    #[must_use]
+    pub fn new(origin: QVec2, dir: QVec2) -> Self {
+        assert!(dir != QVec2::ZERO, "[QRay2::new] Expected a non-zero direction.");
+        Self { origin, dir }
+    }
+
+    /// Returns the point at parameter `t`.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn at(&self, t: Q64) -> QVec2 {
+        self.origin.saturating_add(self.dir.saturating_mul_num(t))
+    }
+
+    /// Returns the first intersection with `shape` for `t` in `[0, max_t]`.
+    ///
+    /// Segment and line queries treat directions whose cross product is within `Q64::EPS` of zero
+    /// as parallel and report no intersection, including when the ray runs along a segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::ray::{QRay2, QSegment2};
+    /// use qmath::shapes::QCircle;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let ray = QRay2::new(qvec2!(0, 0), QVec2::X);
+    ///
+    /// let hit = ray.cast(&QSegment2::new(qvec2!(3, -1), qvec2!(3, 1)), Q64::MAX).unwrap();
+    /// assert_eq!((hit.point, hit.t, hit.normal), (qvec2!(3, 0), q64!(3), QVec2::NEG_X));
+    ///
+    /// let hit = ray.cast(&QCircle::new(qvec2!(5, 0), q64!(1)), Q64::MAX).unwrap();
+    /// assert_eq!((hit.point, hit.t, hit.normal), (qvec2!(4, 0), q64!(4), QVec2::NEG_X));
+    ///
+    /// let aabb = QAabb2::new(qvec2!(2, -1), qvec2!(3, 1));
+    /// assert_eq!(ray.cast(&aabb, Q64::MAX).unwrap().t, q64!(2));
+    /// assert!(ray.cast(&aabb, q64!(1)).is_none());
+    ///
+    /// // Parallel to the segment.
+    /// assert!(ray.cast(&QSegment2::new(qvec2!(1, 0), qvec2!(2, 0)), Q64::MAX).is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn cast<S: QRaycast + ?Sized>(&self, shape: &S, max_t: Q64) -> Option<QRayHit> {
+        shape.raycast(self, max_t)
+    }
+}
+
+impl QSegment2 {
+    /// Creates a new segment.
+    #[inline]
+    #[must_use]
+    pub const fn new(a: QVec2, b: QVec2) -> Self {
+        Self { a, b }
+    }
+
+    /// Returns `b - a`.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn delta(&self) -> QVec2 {
+        self.b.saturating_sub(self.a)
+    }
+
+    /// Returns the length of the segment.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn length(&self) -> Q64 {
+        self.delta().length()
+    }
+
+    /// Returns the point at parameter `t`.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn at(&self, t: Q64) -> QVec2 {
+        self.a.saturating_add(self.delta().saturating_mul_num(t))
+    }
+
+    /// Returns the parameter in `[0, 1]` of the point of the segment closest to `point`.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn closest_t(&self, point: QVec2) -> Q64 {
+        let delta = self.delta();
+        let length_squared = delta.length_squared();
+        if length_squared == Q64::ZERO {
+            return Q64::ZERO;
+        }
+        point.saturating_sub(self.a).dot(delta).saturating_div(length_squared).clamp(Q64::ZERO, Q64::ONE)
+    }
+
+    /// Returns the point of the segment closest to `point`.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn closest_point(&self, point: QVec2) -> QVec2 {
+        self.at(self.closest_t(point))
+    }
+
+    /// Returns the squared distance from `point` to the segment.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn distance_squared_to_point(&self, point: QVec2) -> Q64 {
+        self.closest_point(point).distance_squared(point)
+    }
+
+    /// Returns the intersection with `other`, with `t` along `self` and the normal of `other`.
+    ///
+    /// Returns `None` for near-parallel segments, see [`QRay2::cast`] for the threshold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::ray::QSegment2;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let a = QSegment2::new(qvec2!(0, 0), qvec2!(4, 4));
+    /// let hit = a.intersect_segment(&QSegment2::new(qvec2!(0, 4), qvec2!(4, 0))).unwrap();
+    /// assert_eq!((hit.point, hit.t), (qvec2!(2, 2), Q64::HALF));
+    /// assert!(a.intersect_segment(&QSegment2::new(qvec2!(3, 0), qvec2!(4, 0))).is_none());
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn intersect_segment(&self, other: &Self) -> Option<QRayHit> {
+        let delta = self.delta();
+        let other_delta = other.delta();
+        let (t, u) = solve_lines(self.a, delta, other.a, other_delta)?;
+        let unit = Q64::ZERO..=Q64::ONE;
+        if !unit.contains(&t) || !unit.contains(&u) {
+            return None;
+        }
+        Some(QRayHit {
+            point: self.at(t),
+            t,
+            normal: facing_normal(other_delta, delta),
+        })
+    }
+}
+
+impl QRaycast for QSegment2 {
+    fn raycast(&self, ray: &QRay2, max_t: Q64) -> Option<QRayHit> {
+        let delta = self.delta();
+        let (t, u) = solve_lines(ray.origin, ray.dir, self.a, delta)?;
+        if t < Q64::ZERO || t > max_t || u < Q64::ZERO || u > Q64::ONE {
+            return None;
+        }
+        Some(QRayHit {
+            point: ray.at(t),
+            t,
+            normal: facing_normal(delta, ray.dir),
+        })
+    }
+}
+
+impl QRaycast for QCircle {
+    fn raycast(&self, ray: &QRay2, max_t: Q64) -> Option<QRayHit> {
+        let offset = ray.origin.saturating_sub(self.center);
+        let c = offset.length_squared().saturating_sub(self.radius.saturating_mul(self.radius));
+        if c <= Q64::ZERO {
+            return Some(QRayHit { point: ray.origin, t: Q64::ZERO, normal: inside_normal(ray.dir) });
+        }
+
+        let b = offset.dot(ray.dir);
+        if b > Q64::ZERO {
+            return None;
+        }
+        let a = ray.dir.length_squared();
+        let discriminant = b.saturating_mul(b).saturating_sub(a.saturating_mul(c));
+        if discriminant < Q64::ZERO {
+            return None;
+        }
+
+        let t = (-b - discriminant.sqrt()).saturating_div(a).max(Q64::ZERO);
+        if t > max_t {
+            return None;
+        }
+        let point = ray.at(t);
+        Some(QRayHit { point, t, normal: point.saturating_sub(self.center).normalize() })
+    }
+}
+
+impl QRaycast for QAabb2 {
+    fn raycast(&self, ray: &QRay2, max_t: Q64) -> Option<QRayHit> {
+        let mut t_enter = Q64::MIN;
+        let mut t_exit = Q64::MAX;
+        let mut normal = QVec2::ZERO;
+
+        // Slabs of the X and Y axes.
+        for (origin, dir, min, max, axis) in [
+            (ray.origin.x, ray.dir.x, self.min.x, self.max.x, QVec2::X),
+            (ray.origin.y, ray.dir.y, self.min.y, self.max.y, QVec2::Y),
+        ] {
+            if dir == Q64::ZERO {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+            let t_min = min.saturating_sub(origin).saturating_div(dir);
+            let t_max = max.saturating_sub(origin).saturating_div(dir);
+            let (t_near, t_far, near_normal) = if t_min <= t_max { (t_min, t_max, -axis) } else { (t_max, t_min, axis) };
+            if t_near > t_enter {
+                t_enter = t_near;
+                normal = near_normal;
+            }
+            t_exit = t_exit.min(t_far);
+        }
+
+        if t_enter > t_exit || t_exit < Q64::ZERO || t_enter > max_t {
+            return None;
+        }
+        if t_enter <= Q64::ZERO {
+            return Some(QRayHit { point: ray.origin, t: Q64::ZERO, normal: inside_normal(ray.dir) });
+        }
+        Some(QRayHit { point: ray.at(t_enter), t: t_enter, normal })
+    }
+}
+
+impl QRaycast for QObb2 {
+    fn raycast(&self, ray: &QRay2, max_t: Q64) -> Option<QRayHit> {
+        let [x, y] = self.axes();
+        let local_ray = QRay2 {
+            origin: self.to_local(ray.origin),
+            dir: QVec2::new(ray.dir.dot(x), ray.dir.dot(y)),
+        };
+        let local_box = QAabb2::new(-self.half_extents, self.half_extents);
+        let hit = local_box.raycast(&local_ray, max_t)?;
+        Some(QRayHit {
+            point: ray.at(hit.t),
+            t: hit.t,
+            normal: x.saturating_mul_num(hit.normal.x).saturating_add(y.saturating_mul_num(hit.normal.y)),
+        })
+    }
+}
+
+impl QRaycast for QCapsule2 {
+    fn raycast(&self, ray: &QRay2, max_t: Q64) -> Option<QRayHit> {
+        if self.contains_point(ray.origin) {
+            return Some(QRayHit { point: ray.origin, t: Q64::ZERO, normal: inside_normal(ray.dir) });
+        }
+
+        let mut best: Option<QRayHit> = None;
+        let mut keep_nearest = |hit: Option<QRayHit>| {
+            if let Some(hit) = hit
+                && best.is_none_or(|b| hit.t < b.t)
+            {
+                best = Some(hit);
+            }
+        };
+
+        keep_nearest(QCircle::new(self.a, self.radius).raycast(ray, max_t));
+        keep_nearest(QCircle::new(self.b, self.radius).raycast(ray, max_t));
+        let delta = self.b.saturating_sub(self.a);
+        if delta != QVec2::ZERO {
+            let offset = delta.perp().normalize().saturating_mul_num(self.radius);
+            for side in [offset, -offset] {
+                let edge = QSegment2::new(self.a.saturating_add(side), self.b.saturating_add(side));
+                keep_nearest(edge.raycast(ray, max_t));
+            }
+        }
+        best
+    }
+}
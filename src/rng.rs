@@ -4,6 +4,10 @@ const N: usize = 624;
 const M: usize = 397;
 
 /// Random number generator using the Mersenne Twister algorithm.
+///
+/// The output stream is the reference MT19937 one, so seeding it the same way in other
+/// languages reproduces the same numbers.
+#[derive(Clone)]
 pub struct QRng {
     state: [u32; N],
     index: u32,
@@ -11,6 +15,22 @@ pub struct QRng {
 
 impl QRng {
     /// Initializes the random number seed with a u32 value.
+    ///
+    /// This is the reference `init_genrand`, e.g. the default seed of C++ `std::mt19937`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::rng::QRng;
+    ///
+    /// // `std::mt19937` default constructed, its 10000th output is required by the C++ standard.
+    /// let mut rng = QRng::from_seed(5489u32);
+    /// assert_eq!(rng.next_u32(), 3499211612);
+    /// for _ in 1..9999 {
+    ///     rng.next_u32();
+    /// }
+    /// assert_eq!(rng.next_u32(), 4123659995);
+    /// ```
     pub fn from_seed(seed: u32) -> Self {
         let mut mt = Self {
             state: [0; N],
@@ -18,25 +38,102 @@ impl QRng {
         };
         mt.state[0] = seed;
         for i in 1..N {
-            mt.state[i] = (1812433253_u32.wrapping_mul(mt.state[i - 1] ^ (mt.state[i - 1] >> 30)) + i as u32) & 0xffffffff;
+            mt.state[i] = 1812433253_u32
+                .wrapping_mul(mt.state[i - 1] ^ (mt.state[i - 1] >> 30))
+                .wrapping_add(i as u32);
         }
         mt
     }
 
-    /// Generates a uniformly distributed random number in the range `[0, 1)`.
-    /// 
-    /// Examples
-    /// 
+    /// Initializes the random number seed with an array of u32 values.
+    ///
+    /// This is the reference `init_by_array`.
+    ///
+    /// # Examples
+    ///
     /// ```
-    /// use qmath::prelude::*;
-    /// 
-    /// let mut rng = QRng::from_seed(12u32);
-    /// for _ in 0..1_000_000 {
-    ///     let random = rng.next_unit();
-    ///     assert!(random >= 0 && random < 1);
+    /// use qmath::rng::QRng;
+    ///
+    /// // The first outputs of the reference `mt19937ar.out`.
+    /// let mut rng = QRng::from_seed_array(&[0x123, 0x234, 0x345, 0x456]);
+    /// let expected = [1067595299u32, 955945823, 477289528, 4107218783, 4228976476];
+    /// for value in expected {
+    ///     assert_eq!(rng.next_u32(), value);
     /// }
     /// ```
-    pub fn next_unit(&mut self) -> Q64 {
+    ///
+    /// # Panics
+    ///
+    /// ```rust,should_panic
+    /// use qmath::rng::QRng;
+    /// let _ = QRng::from_seed_array(&[]);
+    /// ```
+    pub fn from_seed_array(key: &[u32]) -> Self {
+        assert!(!key.is_empty(), "[QRng::from_seed_array] Expected a non-empty key.");
+        let mut mt = Self::from_seed(19650218_u32);
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..N.max(key.len()) {
+            let prev = mt.state[i - 1] ^ (mt.state[i - 1] >> 30);
+            mt.state[i] = (mt.state[i] ^ prev.wrapping_mul(1664525_u32))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                mt.state[0] = mt.state[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..N - 1 {
+            let prev = mt.state[i - 1] ^ (mt.state[i - 1] >> 30);
+            mt.state[i] = (mt.state[i] ^ prev.wrapping_mul(1566083941_u32))
+                .wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                mt.state[0] = mt.state[N - 1];
+                i = 1;
+            }
+        }
+        mt.state[0] = 0x80000000_u32;
+        mt
+    }
+
+    /// Initializes the random number seed with a u64 value.
+    ///
+    /// The seed is split into its 32-bit words, least significant first, with a zero high word
+    /// dropped, and passed to [`from_seed_array`][Self::from_seed_array()]. This matches
+    /// Python's `random.seed(seed)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::rng::QRng;
+    ///
+    /// // `random.seed(0x123456789abcdef0)` then `random.getrandbits(32)` in Python.
+    /// let mut rng = QRng::from_seed_u64(0x123456789abcdef0);
+    /// assert_eq!(rng.next_u32(), 3646699384);
+    /// assert_eq!(rng.next_u32(), 2523371432);
+    ///
+    /// // `random.seed(5489)`.
+    /// let mut rng = QRng::from_seed_u64(5489);
+    /// assert_eq!(rng.next_u32(), 3382763572);
+    /// ```
+    pub fn from_seed_u64(seed: u64) -> Self {
+        let low = seed as u32;
+        let high = (seed >> 32) as u32;
+        if high == 0 {
+            Self::from_seed_array(&[low])
+        } else {
+            Self::from_seed_array(&[low, high])
+        }
+    }
+
+    /// Generates the next raw 32-bit output of the generator.
+    pub fn next_u32(&mut self) -> u32 {
         if self.index >= N as u32 {
             self.twist();
         }
@@ -48,14 +145,70 @@ impl QRng {
         y ^= y >> 18;
 
         self.index += 1;
-        Q64::from_bits(y as i64)
+        y
+    }
+
+    /// Generates a uniformly distributed random integer in the range `[0, bound)`.
+    ///
+    /// Uses Lemire's multiply-and-reject method, so the result is unbiased.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(12u32);
+    /// let mut counts = [0; 6];
+    /// for _ in 0..6000 {
+    ///     counts[rng.next_below(6) as usize] += 1;
+    /// }
+    /// assert!(counts.iter().all(|count| *count > 900 && *count < 1100));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// ```rust,should_panic
+    /// use qmath::rng::QRng;
+    /// let _ = QRng::from_seed(12u32).next_below(0);
+    /// ```
+    pub fn next_below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "[QRng::next_below] Expected bound > 0.");
+        let mut product = self.next_u32() as u64 * bound as u64;
+        if (product as u32) < bound {
+            let threshold = bound.wrapping_neg() % bound;
+            while (product as u32) < threshold {
+                product = self.next_u32() as u64 * bound as u64;
+            }
+        }
+        (product >> 32) as u32
+    }
+
+    /// Generates a uniformly distributed random number in the range `[0, 1)`.
+    ///
+    /// The 32 output bits are used as the fractional bits of the result, so every
+    /// representable value in the range is equally likely.
+    ///
+    /// Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(12u32);
+    /// for _ in 0..1_000_000 {
+    ///     let random = rng.next_unit();
+    ///     assert!(random >= 0 && random < 1);
+    /// }
+    /// ```
+    pub fn next_unit(&mut self) -> Q64 {
+        Q64::from_bits(self.next_u32() as i64)
     }
 
     fn twist(&mut self) {
         for i in 0..N {
             let x = (self.state[i] & 0x80000000_u32) + (self.state[(i + 1) % N] & 0x7fffffff_u32);
             let mut x_a = x >> 1;
-            if x % 2 != 0 {
+            if x & 1 != 0 {
                 x_a ^= 0x9908b0df_u32;
             }
             self.state[i] = self.state[(i + M) % N] ^ x_a;
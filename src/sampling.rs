@@ -0,0 +1,161 @@
+use crate::prelude::*;
+use crate::dir::QDir;
+use crate::rng::QRng;
+use crate::vec2::QVec2;
+
+/// Geometric sampling.
+///
+/// All samplers only use fixed-point arithmetic, so a given seed produces the same points on
+/// every platform.
+impl QRng {
+    /// Generates a uniformly distributed random direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(3u32);
+    /// for _ in 0..1000 {
+    ///     let dir = rng.unit_dir();
+    ///     assert!(dir.angle() >= 0 && dir.angle() < Q64::TWO_PI);
+    /// }
+    /// ```
+    pub fn unit_dir(&mut self) -> QDir {
+        QDir::new(self.next_unit() * Q64::TWO_PI)
+    }
+
+    /// Generates a uniformly distributed random point on the unit circle.
+    ///
+    /// The length of the result is `1` within the precision of [`QVec2::from_angle`].
+    pub fn on_unit_circle(&mut self) -> QVec2 {
+        self.unit_dir().to_vec()
+    }
+
+    /// Generates a uniformly distributed random point inside the unit disk.
+    ///
+    /// Points are drawn from the enclosing square and rejected until one falls strictly inside
+    /// the disk, so the result is exactly uniform over the area.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(3u32);
+    /// for _ in 0..1000 {
+    ///     assert!(rng.in_unit_disk().length_squared() < 1);
+    /// }
+    /// ```
+    pub fn in_unit_disk(&mut self) -> QVec2 {
+        loop {
+            let point = QVec2::new(
+                self.next_unit() * 2 - Q64::ONE,
+                self.next_unit() * 2 - Q64::ONE,
+            );
+            if point.length_squared() < Q64::ONE {
+                return point;
+            }
+        }
+    }
+
+    /// Generates a uniformly distributed random point inside the circle at `center` with `radius`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `radius` is negative.
+    ///
+    /// SAT
+    pub fn in_circle(&mut self, center: QVec2, radius: Q64) -> QVec2 {
+        assert!(radius >= Q64::ZERO, "[QRng::in_circle] Expected radius >= 0.");
+        center.saturating_add(self.in_unit_disk().saturating_mul_num(radius))
+    }
+
+    /// Generates a uniformly distributed random point inside the annulus centered at the origin
+    /// with inner radius `r0` and outer radius `r1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(3u32);
+    /// for _ in 0..1000 {
+    ///     let length = rng.in_annulus(q64!(2), q64!(3)).length();
+    ///     assert!(length >= q64!(2) - Q64::EPS && length <= q64!(3) + Q64::EPS);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `0 <= r0 <= r1` does not hold.
+    ///
+    /// SAT
+    pub fn in_annulus(&mut self, r0: Q64, r1: Q64) -> QVec2 {
+        assert!(r0 >= Q64::ZERO && r0 <= r1, "[QRng::in_annulus] Expected 0 <= r0 <= r1.");
+        let r0_squared = r0.saturating_mul(r0);
+        let r1_squared = r1.saturating_mul(r1);
+        let radius = (r0_squared + (r1_squared - r0_squared) * self.next_unit())
+            .sqrt()
+            .clamp(r0, r1);
+        self.on_unit_circle().saturating_mul_num(radius)
+    }
+
+    /// Generates a uniformly distributed random point inside the rectangle `[min, max)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut rng = QRng::from_seed(3u32);
+    /// let (min, max) = (qvec2!(-1, 2), qvec2!(4, 3));
+    /// for _ in 0..1000 {
+    ///     let point = rng.in_rect(min, max);
+    ///     assert!(point.x >= min.x && point.x < max.x && point.y >= min.y && point.y < max.y);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `min` is greater than `max`.
+    pub fn in_rect(&mut self, min: QVec2, max: QVec2) -> QVec2 {
+        assert!(min.x <= max.x && min.y <= max.y, "[QRng::in_rect] Expected min <= max.");
+        QVec2::new(self.uniform(min.x, max.x), self.uniform(min.y, max.y))
+    }
+
+    /// Generates a uniformly distributed random point inside the triangle `abc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut rng = QRng::from_seed(3u32);
+    /// let (a, b, c) = (qvec2!(0, 0), qvec2!(4, 0), qvec2!(0, 2));
+    /// for _ in 0..1000 {
+    ///     let point = rng.in_triangle(a, b, c);
+    ///     assert!(point.x >= 0 && point.y >= 0 && point.x + point.y * 2 <= 4);
+    /// }
+    /// ```
+    ///
+    /// SAT
+    pub fn in_triangle(&mut self, a: QVec2, b: QVec2, c: QVec2) -> QVec2 {
+        let mut u = self.next_unit();
+        let mut v = self.next_unit();
+        if u + v > Q64::ONE {
+            u = Q64::ONE - u;
+            v = Q64::ONE - v;
+        }
+        let ab = b.saturating_sub(a).saturating_mul_num(u);
+        let ac = c.saturating_sub(a).saturating_mul_num(v);
+        a.saturating_add(ab).saturating_add(ac)
+    }
+}
@@ -0,0 +1,362 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::polygon::{QPolygon, QWinding};
+use crate::shapes::{QCircle, QObb2};
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+
+/// How much deeper the second polygon's best axis must be before its face is used as the
+/// reference face, so that nearly equal axes don't make the manifold flicker between frames.
+const REFERENCE_FACE_TOLERANCE: Q64 = Q64::lit("0.0005");
+
+/// A contact point of a manifold.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QContact {
+    /// The contact point, halfway between the surfaces of the two shapes.
+    pub point: QVec2,
+    /// The penetration depth along the manifold normal at this point, non-negative.
+    pub depth: Q64,
+}
+
+/// The contact manifold of two overlapping convex shapes.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QManifold {
+    /// Unit normal pointing from the first shape to the second.
+    pub normal: QVec2,
+    contacts: [QContact; 2],
+    count: usize,
+}
+
+impl QManifold {
+    fn new(normal: QVec2, contacts: &[QContact]) -> Self {
+        let mut manifold = Self { normal, contacts: [QContact::default(); 2], count: contacts.len() };
+        manifold.contacts[..contacts.len()].copy_from_slice(contacts);
+        manifold
+    }
+
+    /// Returns the one or two contact points.
+    #[inline]
+    #[must_use]
+    pub fn contacts(&self) -> &[QContact] {
+        &self.contacts[..self.count]
+    }
+
+    /// Returns the deepest penetration of the contact points.
+    #[must_use]
+    pub fn depth(&self) -> Q64 {
+        self.contacts().iter().fold(Q64::ZERO, |acc, c| acc.max(c.depth))
+    }
+
+    /// Returns the manifold seen from the second shape.
+    #[must_use]
+    pub fn flipped(&self) -> Self {
+        Self { normal: -self.normal, ..*self }
+    }
+}
+
+/// A convex shape for the separating axis test.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QConvex {
+    Circle(QCircle),
+    /// A convex polygon, in either winding.
+    Polygon(QPolygon),
+}
+
+impl From<QCircle> for QConvex {
+    fn from(circle: QCircle) -> Self {
+        Self::Circle(circle)
+    }
+}
+
+impl From<QPolygon> for QConvex {
+    fn from(polygon: QPolygon) -> Self {
+        Self::Polygon(polygon)
+    }
+}
+
+impl From<QObb2> for QConvex {
+    fn from(obb: QObb2) -> Self {
+        Self::Polygon(obb.into())
+    }
+}
+
+impl From<QAabb2> for QConvex {
+    fn from(aabb: QAabb2) -> Self {
+        Self::Polygon(aabb.into())
+    }
+}
+
+/// Collides two convex shapes, returning `None` if they are separated.
+///
+/// Touching shapes collide with a zero depth.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::aabb::QAabb2;
+/// use qmath::polygon::QPolygon;
+/// use qmath::sat::{collide, QConvex};
+/// use qmath::shapes::QCircle;
+/// use qmath::vec2::QVec2;
+///
+/// let ground = QConvex::from(QAabb2::new(qvec2!(-5, -1), qvec2!(5, 0)));
+/// let crate_box = QConvex::from(QAabb2::new(qvec2!(-1, -0.25), qvec2!(1, 1.75)));
+/// let manifold = collide(&ground, &crate_box).unwrap();
+/// assert_eq!(manifold.normal, QVec2::Y);
+/// assert_eq!(manifold.contacts().len(), 2);
+/// assert!(manifold.contacts().iter().all(|c| c.depth == q64!(0.25) && c.point.y == q64!(-0.125)));
+///
+/// let ball = QConvex::from(QCircle::new(qvec2!(3, 0.5), q64!(1)));
+/// let manifold = collide(&ground, &ball).unwrap();
+/// assert_eq!((manifold.normal, manifold.depth()), (QVec2::Y, q64!(0.5)));
+///
+/// assert!(collide(&crate_box, &QConvex::from(QCircle::new(qvec2!(3, 0.5), q64!(0.5)))).is_none());
+///
+/// // Flat boxes and repeated vertices are fine.
+/// let plank = QConvex::from(QAabb2::new(qvec2!(-1, 0), qvec2!(1, 0)));
+/// let manifold = collide(&plank, &crate_box).unwrap();
+/// assert_eq!((manifold.normal, manifold.depth()), (QVec2::Y, q64!(0.25)));
+/// let wedge = QConvex::from(QPolygon::new(vec![qvec2!(0, 0), qvec2!(2, 0), qvec2!(2, 0), qvec2!(0, 2)]));
+/// assert!(collide(&wedge, &QConvex::from(QCircle::new(qvec2!(2, 1), q64!(1)))).is_some());
+/// assert!(collide(&wedge, &crate_box).is_some());
+///
+/// // Long edges still give unit normals.
+/// let slope = QConvex::from(QPolygon::new(vec![qvec2!(0, 0), qvec2!(60000, 0), qvec2!(0, 60000)]));
+/// let pebble = QConvex::from(QAabb2::new(qvec2!(29999, 29999), qvec2!(30001, 30002)));
+/// let manifold = collide(&slope, &pebble).unwrap();
+/// assert!(manifold.normal.is_normalized() && manifold.normal.x == manifold.normal.y);
+/// ```
+#[must_use]
+pub fn collide(a: &QConvex, b: &QConvex) -> Option<QManifold> {
+    match (a, b) {
+        (QConvex::Circle(a), QConvex::Circle(b)) => collide_circles(a, b),
+        (QConvex::Polygon(a), QConvex::Circle(b)) => collide_polygon_circle(a, b),
+        (QConvex::Circle(a), QConvex::Polygon(b)) => collide_polygon_circle(b, a).map(|m| m.flipped()),
+        (QConvex::Polygon(a), QConvex::Polygon(b)) => collide_polygons(a, b),
+    }
+}
+
+/// Collides two circles.
+///
+/// Concentric circles are pushed apart along the X axis.
+///
+/// SAT
+#[must_use]
+pub fn collide_circles(a: &QCircle, b: &QCircle) -> Option<QManifold> {
+    let offset = b.center.saturating_sub(a.center);
+    let radii = a.radius.saturating_add(b.radius);
+    if offset.length_squared() > radii.saturating_mul(radii) {
+        return None;
+    }
+
+    let distance = offset.length();
+    let normal = if distance == Q64::ZERO { QVec2::X } else { offset.saturating_div_num(distance) };
+    let depth = radii - distance;
+    let point = a.center.saturating_add(normal.saturating_mul_num(a.radius - depth.half()));
+    Some(QManifold::new(normal, &[QContact { point, depth }]))
+}
+
+/// Collides a convex polygon with a circle.
+///
+/// SAT
+#[must_use]
+pub fn collide_polygon_circle(a: &QPolygon, b: &QCircle) -> Option<QManifold> {
+    let vertices = ccw_vertices(a);
+    let n = vertices.len();
+
+    // The face the center is furthest in front of.
+    let mut face = 0;
+    let mut separation = Q64::MIN;
+    for i in 0..n {
+        let Some(normal) = outward_normal(vertices[i], vertices[(i + 1) % n]) else {
+            continue;
+        };
+        let s = normal.dot(b.center.saturating_sub(vertices[i]));
+        if s > separation {
+            separation = s;
+            face = i;
+        }
+    }
+    if separation > b.radius {
+        return None;
+    }
+
+    let v1 = vertices[face];
+    let v2 = vertices[(face + 1) % n];
+    let Some(face_normal) = outward_normal(v1, v2) else {
+        // No edge has a length, the polygon is a point.
+        return collide_point_circle(v1, b, QVec2::X);
+    };
+    let face_contact = |s: Q64| {
+        let point = b.center.saturating_sub(face_normal.saturating_mul_num((b.radius.saturating_add(s)).half()));
+        QManifold::new(face_normal, &[QContact { point, depth: b.radius - s }])
+    };
+    if separation <= Q64::ZERO {
+        return Some(face_contact(separation));
+    }
+
+    // The center is outside, it is closest to either a vertex or the face.
+    for (vertex, other) in [(v1, v2), (v2, v1)] {
+        if b.center.saturating_sub(vertex).dot(other.saturating_sub(vertex)) <= Q64::ZERO {
+            return collide_point_circle(vertex, b, face_normal);
+        }
+    }
+    Some(face_contact(separation))
+}
+
+/// Collides a vertex with a circle, pushing along `fallback` if the center is on the vertex.
+///
+/// SAT
+fn collide_point_circle(vertex: QVec2, b: &QCircle, fallback: QVec2) -> Option<QManifold> {
+    let offset = b.center.saturating_sub(vertex);
+    let distance_squared = offset.length_squared();
+    if distance_squared > b.radius.saturating_mul(b.radius) {
+        return None;
+    }
+    let distance = distance_squared.sqrt();
+    let normal = offset.try_normalize().unwrap_or(fallback);
+    let depth = b.radius - distance;
+    let point = vertex.saturating_sub(normal.saturating_mul_num(depth.half()));
+    Some(QManifold::new(normal, &[QContact { point, depth }]))
+}
+
+/// Collides two convex polygons.
+///
+/// The face of deepest penetration is used as the reference face, and the most anti-parallel
+/// face of the other polygon is clipped against it to produce up to two contact points.
+///
+/// SAT
+#[must_use]
+pub fn collide_polygons(a: &QPolygon, b: &QPolygon) -> Option<QManifold> {
+    let a = ccw_vertices(a);
+    let b = ccw_vertices(b);
+
+    let (face_a, separation_a) = max_separation(&a, &b);
+    if separation_a > Q64::ZERO {
+        return None;
+    }
+    let (face_b, separation_b) = max_separation(&b, &a);
+    if separation_b > Q64::ZERO {
+        return None;
+    }
+
+    let (reference, incident, face, flip) = if separation_b > separation_a.saturating_add(REFERENCE_FACE_TOLERANCE) {
+        (&b, &a, face_b, true)
+    } else {
+        (&a, &b, face_a, false)
+    };
+
+    let v1 = reference[face];
+    let v2 = reference[(face + 1) % reference.len()];
+    // Only two points have no face to clip against.
+    let tangent = v2.saturating_sub(v1).try_normalize()?;
+    let normal = QVec2::new(tangent.y, -tangent.x);
+
+    // The incident face is the most anti-parallel to the reference normal.
+    let m = incident.len();
+    let mut incident_face = 0;
+    let mut min_dot = Q64::MAX;
+    for i in 0..m {
+        let Some(d) = outward_normal(incident[i], incident[(i + 1) % m]).map(|n| n.dot(normal)) else {
+            continue;
+        };
+        if d < min_dot {
+            min_dot = d;
+            incident_face = i;
+        }
+    }
+    let segment = [incident[incident_face], incident[(incident_face + 1) % m]];
+
+    // Clips the incident face to the side planes of the reference face.
+    let segment = clip_segment(segment, -tangent, -tangent.dot(v1))?;
+    let segment = clip_segment(segment, tangent, tangent.dot(v2))?;
+
+    let mut contacts = [QContact::default(); 2];
+    let mut count = 0;
+    for point in segment {
+        let separation = normal.dot(point.saturating_sub(v1));
+        if separation <= Q64::ZERO {
+            let depth = -separation;
+            contacts[count] = QContact { point: point.saturating_add(normal.saturating_mul_num(depth.half())), depth };
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return None;
+    }
+
+    let normal = if flip { -normal } else { normal };
+    Some(QManifold::new(normal, &contacts[..count]))
+}
+
+/// Returns the vertices of a polygon in counter-clockwise order, without repeated consecutive
+/// vertices so that no edge has a zero length.
+fn ccw_vertices(polygon: &QPolygon) -> Vec<QVec2> {
+    let mut vertices = polygon.vertices().to_vec();
+    if polygon.winding() == QWinding::Clockwise {
+        vertices.reverse();
+    }
+    vertices.dedup();
+    while vertices.len() > 1 && vertices[0] == vertices[vertices.len() - 1] {
+        vertices.pop();
+    }
+    vertices
+}
+
+/// Returns the outward unit normal of the edge from `p` to `q` of a counter-clockwise polygon,
+/// or `None` if the edge is too short to have one.
+///
+/// SAT
+fn outward_normal(p: QVec2, q: QVec2) -> Option<QVec2> {
+    let edge = q.saturating_sub(p).try_normalize()?;
+    Some(QVec2::new(edge.y, -edge.x))
+}
+
+/// Returns the face of `a` along which `b` is the least deep, and that separation.
+///
+/// SAT
+fn max_separation(a: &[QVec2], b: &[QVec2]) -> (usize, Q64) {
+    let n = a.len();
+    let mut best_face = 0;
+    let mut best = Q64::MIN;
+    for i in 0..n {
+        let Some(normal) = outward_normal(a[i], a[(i + 1) % n]) else {
+            continue;
+        };
+        let separation = b
+            .iter()
+            .map(|v| normal.dot(v.saturating_sub(a[i])))
+            .min()
+            .unwrap_or(Q64::MAX);
+        if separation > best {
+            best = separation;
+            best_face = i;
+        }
+    }
+    (best_face, best)
+}
+
+/// Keeps the part of `segment` where `normal.dot(p) <= offset`, or `None` if less than two points remain.
+///
+/// SAT
+fn clip_segment(segment: [QVec2; 2], normal: QVec2, offset: Q64) -> Option<[QVec2; 2]> {
+    let d0 = normal.dot(segment[0]).saturating_sub(offset);
+    let d1 = normal.dot(segment[1]).saturating_sub(offset);
+    let mut out = [QVec2::ZERO; 2];
+    let mut count = 0;
+    if d0 <= Q64::ZERO {
+        out[count] = segment[0];
+        count += 1;
+    }
+    if d1 <= Q64::ZERO {
+        out[count] = segment[1];
+        count += 1;
+    }
+    if (d0 < Q64::ZERO) != (d1 < Q64::ZERO) && count < 2 {
+        let t = d0.saturating_div(d0 - d1);
+        out[count] = segment[0].lerp(segment[1], t);
+        count += 1;
+    }
+    (count == 2).then_some(out)
+}
@@ -0,0 +1,186 @@
+use crate::prelude::*;
+use crate::rng::QRng;
+
+/// Random operations on slices.
+///
+/// Only integer arithmetic is used, so a given seed produces the same results on every platform.
+impl QRng {
+    /// Generates a uniformly distributed random index in the range `[0, length)`.
+    fn next_index(&mut self, length: usize) -> usize {
+        assert!(length <= u32::MAX as usize, "[QRng::next_index] Expected length <= u32::MAX.");
+        self.next_below(length as u32) as usize
+    }
+
+    /// Shuffles `slice` in place with the Fisher-Yates algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(5u32);
+    /// let mut values: Vec<u32> = (0..100).collect();
+    /// rng.shuffle(&mut values);
+    /// assert_ne!(values, (0..100).collect::<Vec<u32>>());
+    /// values.sort();
+    /// assert_eq!(values, (0..100).collect::<Vec<u32>>());
+    /// ```
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.next_index(i + 1);
+            slice.swap(i, j);
+        }
+    }
+
+    /// Returns a uniformly chosen element of `slice`, or `None` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(5u32);
+    /// assert!(rng.choose::<u32>(&[]).is_none());
+    /// assert!([1, 2, 3].contains(rng.choose(&[1, 2, 3]).unwrap()));
+    /// ```
+    pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            return None;
+        }
+        Some(&slice[self.next_index(slice.len())])
+    }
+
+    /// Returns `amount` distinct elements of `slice` in random order.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `amount` is greater than the length of `slice`.
+    pub fn choose_multiple<'a, T>(&mut self, slice: &'a [T], amount: usize) -> Vec<&'a T> {
+        self.sample_indices(slice.len(), amount)
+            .into_iter()
+            .map(|index| &slice[index])
+            .collect()
+    }
+
+    /// Returns `amount` distinct indices in the range `[0, length)` in random order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::rng::QRng;
+    ///
+    /// let mut rng = QRng::from_seed(5u32);
+    /// let mut indices = rng.sample_indices(10, 4);
+    /// assert_eq!(indices.len(), 4);
+    /// indices.sort();
+    /// indices.dedup();
+    /// assert_eq!(indices.len(), 4);
+    /// assert!(indices.iter().all(|index| *index < 10));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `amount` is greater than `length`.
+    pub fn sample_indices(&mut self, length: usize, amount: usize) -> Vec<usize> {
+        assert!(amount <= length, "[QRng::sample_indices] Expected amount <= length.");
+        let mut indices: Vec<usize> = (0..length).collect();
+        for i in 0..amount {
+            let j = i + self.next_index(length - i);
+            indices.swap(i, j);
+        }
+        indices.truncate(amount);
+        indices
+    }
+}
+
+/// Weighted random selection of indices using Vose's alias method.
+///
+/// Construction is `O(n)` and each sample is `O(1)`. The table is built with integer arithmetic
+/// on the raw bits of the weights, so it is identical on every platform.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::rng::QRng;
+/// use qmath::seq::QWeightedIndex;
+///
+/// let weights = [q64!(1), q64!(0), q64!(3)];
+/// let index = QWeightedIndex::new(&weights);
+/// let mut rng = QRng::from_seed(5u32);
+/// let mut counts = [0; 3];
+/// for _ in 0..4000 {
+///     counts[index.sample(&mut rng)] += 1;
+/// }
+/// assert_eq!(counts[1], 0);
+/// assert!(counts[0] > 900 && counts[0] < 1100);
+/// assert!(counts[2] > 2900 && counts[2] < 3100);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QWeightedIndex {
+    /// Probability of keeping the column, out of `2^32`.
+    thresholds: Vec<u64>,
+    aliases: Vec<usize>,
+}
+
+impl QWeightedIndex {
+    /// Builds the alias table for `weights`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `weights` is empty, has more than `u32::MAX` elements, contains a negative
+    /// weight or sums to zero.
+    pub fn new(weights: &[Q64]) -> Self {
+        assert!(!weights.is_empty(), "[QWeightedIndex::new] Expected at least one weight.");
+        assert!(weights.len() <= u32::MAX as usize, "[QWeightedIndex::new] Expected at most u32::MAX weights.");
+        assert!(weights.iter().all(|w| *w >= Q64::ZERO), "[QWeightedIndex::new] Expected non-negative weights.");
+
+        let n = weights.len() as u128;
+        let total: u128 = weights.iter().map(|w| w.to_bits() as u128).sum();
+        assert!(total > 0, "[QWeightedIndex::new] Expected a positive total weight.");
+
+        // Scaled so that the average column holds exactly `total`.
+        let mut scaled: Vec<u128> = weights.iter().map(|w| w.to_bits() as u128 * n).collect();
+        let mut thresholds = vec![1_u64 << 32; weights.len()];
+        let mut aliases: Vec<usize> = (0..weights.len()).collect();
+
+        let mut small: Vec<usize> = Vec::new();
+        let mut large: Vec<usize> = Vec::new();
+        for (i, s) in scaled.iter().enumerate() {
+            if *s < total { small.push(i) } else { large.push(i) }
+        }
+
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            thresholds[less] = ((scaled[less] << 32) / total) as u64;
+            aliases[less] = more;
+            scaled[more] = scaled[more] + scaled[less] - total;
+            if scaled[more] < total {
+                large.pop();
+                small.push(more);
+            }
+        }
+
+        Self { thresholds, aliases }
+    }
+
+    /// Returns the number of weights.
+    pub fn len(&self) -> usize {
+        self.thresholds.len()
+    }
+
+    /// Returns whether there are no weights, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        self.thresholds.is_empty()
+    }
+
+    /// Samples an index with probability proportional to its weight.
+    pub fn sample(&self, rng: &mut QRng) -> usize {
+        let column = rng.next_below(self.thresholds.len() as u32) as usize;
+        if (rng.next_u32() as u64) < self.thresholds[column] {
+            column
+        } else {
+            self.aliases[column]
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::dir::QDir;
+use crate::ray::QSegment2;
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+
+/// A circle.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QCircle {
+    pub center: QVec2,
+    pub radius: Q64,
+}
+
+impl QCircle {
+    /// Creates a new circle.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `radius` is negative.
+    #[inline]
+    #[must_use]
+    pub fn new(center: QVec2, radius: Q64) -> Self {
+        assert!(radius >= Q64::ZERO, "[QCircle::new] Expected radius >= 0.");
+        Self { center, radius }
+    }
+
+    /// Returns whether `point` is inside the circle or on its boundary.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn contains_point(&self, point: QVec2) -> bool {
+        self.center.distance_squared(point) <= self.radius.saturating_mul(self.radius)
+    }
+
+    /// Returns the point of the circle closest to `point`, which is `point` itself if it is inside.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::shapes::QCircle;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let circle = QCircle::new(qvec2!(1, 1), q64!(2));
+    /// assert!(circle.closest_point(qvec2!(1, 5)).abs_diff_eq(qvec2!(1, 3), Q64::EPS));
+    /// assert_eq!(circle.closest_point(qvec2!(2, 1)), qvec2!(2, 1));
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn closest_point(&self, point: QVec2) -> QVec2 {
+        if self.contains_point(point) {
+            return point;
+        }
+        let offset = point.saturating_sub(self.center).normalize();
+        self.center.saturating_add(offset.saturating_mul_num(self.radius))
+    }
+
+    /// Returns the point of the circle furthest along `dir`, or the center if `dir` is zero.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn support(&self, dir: QVec2) -> QVec2 {
+        if dir == QVec2::ZERO {
+            return self.center;
+        }
+        self.center.saturating_add(dir.normalize().saturating_mul_num(self.radius))
+    }
+
+    /// Returns the bounding box of the circle.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn aabb(&self) -> QAabb2 {
+        QAabb2::from_center_half_extents(self.center, QVec2::splat(self.radius))
+    }
+}
+
+/// A capsule, the set of points within `radius` of the segment `ab`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QCapsule2 {
+    pub a: QVec2,
+    pub b: QVec2,
+    pub radius: Q64,
+}
+
+impl QCapsule2 {
+    /// Creates a new capsule.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `radius` is negative.
+    #[inline]
+    #[must_use]
+    pub fn new(a: QVec2, b: QVec2, radius: Q64) -> Self {
+        assert!(radius >= Q64::ZERO, "[QCapsule2::new] Expected radius >= 0.");
+        Self { a, b, radius }
+    }
+
+    /// Returns the inner segment.
+    #[inline]
+    #[must_use]
+    pub fn segment(&self) -> QSegment2 {
+        QSegment2::new(self.a, self.b)
+    }
+
+    /// Returns the point of the inner segment closest to `point`.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn closest_point_on_segment(&self, point: QVec2) -> QVec2 {
+        self.segment().closest_point(point)
+    }
+
+    /// Returns whether `point` is inside the capsule or on its boundary.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn contains_point(&self, point: QVec2) -> bool {
+        self.closest_point_on_segment(point).distance_squared(point) <= self.radius.saturating_mul(self.radius)
+    }
+
+    /// Returns the point of the capsule closest to `point`, which is `point` itself if it is inside.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::shapes::QCapsule2;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let capsule = QCapsule2::new(qvec2!(0, 0), qvec2!(4, 0), q64!(1));
+    /// assert!(capsule.closest_point(qvec2!(2, 3)).abs_diff_eq(qvec2!(2, 1), Q64::EPS));
+    /// assert!(capsule.closest_point(qvec2!(7, 0)).abs_diff_eq(qvec2!(5, 0), Q64::EPS));
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn closest_point(&self, point: QVec2) -> QVec2 {
+        QCircle::new(self.closest_point_on_segment(point), self.radius).closest_point(point)
+    }
+
+    /// Returns the point of the capsule furthest along `dir`.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn support(&self, dir: QVec2) -> QVec2 {
+        let end = if self.a.dot(dir) >= self.b.dot(dir) { self.a } else { self.b };
+        QCircle::new(end, self.radius).support(dir)
+    }
+
+    /// Returns the bounding box of the capsule.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn aabb(&self) -> QAabb2 {
+        QAabb2::from_points(&[self.a, self.b]).expand(self.radius)
+    }
+}
+
+/// An oriented box, rotated by `rotation` around its center.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QObb2 {
+    pub center: QVec2,
+    pub half_extents: QVec2,
+    pub rotation: QDir,
+}
+
+impl QObb2 {
+    /// Creates a new oriented box.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `half_extents` is negative.
+    #[inline]
+    #[must_use]
+    pub fn new(center: QVec2, half_extents: QVec2, rotation: QDir) -> Self {
+        assert!(half_extents.x >= Q64::ZERO && half_extents.y >= Q64::ZERO, "[QObb2::new] Expected half_extents >= 0.");
+        Self { center, half_extents, rotation }
+    }
+
+    /// Returns the local X and Y axes of the box in world space.
+    #[inline]
+    #[must_use]
+    pub fn axes(&self) -> [QVec2; 2] {
+        let x = self.rotation.to_vec();
+        [x, x.perp()]
+    }
+
+    /// Transforms a world space point into the local space of the box.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn to_local(&self, point: QVec2) -> QVec2 {
+        let [x, y] = self.axes();
+        let offset = point.saturating_sub(self.center);
+        QVec2::new(offset.dot(x), offset.dot(y))
+    }
+
+    /// Transforms a point in the local space of the box into world space.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn to_world(&self, point: QVec2) -> QVec2 {
+        let [x, y] = self.axes();
+        self.center.saturating_add(x.saturating_mul_num(point.x)).saturating_add(y.saturating_mul_num(point.y))
+    }
+
+    /// Returns the corners of the box in CCW order.
+    #[must_use]
+    pub fn corners(&self) -> [QVec2; 4] {
+        let (hx, hy) = (self.half_extents.x, self.half_extents.y);
+        [
+            self.to_world(QVec2::new(-hx, -hy)),
+            self.to_world(QVec2::new(hx, -hy)),
+            self.to_world(QVec2::new(hx, hy)),
+            self.to_world(QVec2::new(-hx, hy)),
+        ]
+    }
+
+    /// Returns whether `point` is inside the box or on its boundary.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn contains_point(&self, point: QVec2) -> bool {
+        let local = self.to_local(point).abs();
+        local.x <= self.half_extents.x && local.y <= self.half_extents.y
+    }
+
+    /// Returns the point of the box closest to `point`, which is `point` itself if it is inside.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::dir::QDir;
+    /// use qmath::shapes::QObb2;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let obb = QObb2::new(qvec2!(0, 0), qvec2!(2, 1), QDir::new(Q64::FRAC_PI_2));
+    /// assert!(obb.contains_point(qvec2!(0.5, 1.5)));
+    /// assert!(!obb.contains_point(qvec2!(1.5, 0.5)));
+    /// assert!(obb.closest_point(qvec2!(3, 0)).abs_diff_eq(qvec2!(1, 0), Q64::EPS));
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn closest_point(&self, point: QVec2) -> QVec2 {
+        if self.contains_point(point) {
+            return point;
+        }
+        let local = self.to_local(point).clamp(-self.half_extents, self.half_extents);
+        self.to_world(local)
+    }
+
+    /// Returns the corner of the box furthest along `dir`.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn support(&self, dir: QVec2) -> QVec2 {
+        let [x, y] = self.axes();
+        let hx = if dir.dot(x) >= Q64::ZERO { self.half_extents.x } else { -self.half_extents.x };
+        let hy = if dir.dot(y) >= Q64::ZERO { self.half_extents.y } else { -self.half_extents.y };
+        self.to_world(QVec2::new(hx, hy))
+    }
+
+    /// Returns the bounding box of the oriented box.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn aabb(&self) -> QAabb2 {
+        let [x, y] = self.axes();
+        let extents = QVec2::new(
+            x.x.abs().saturating_mul(self.half_extents.x).saturating_add(y.x.abs().saturating_mul(self.half_extents.y)),
+            x.y.abs().saturating_mul(self.half_extents.x).saturating_add(y.y.abs().saturating_mul(self.half_extents.y)),
+        );
+        QAabb2::from_center_half_extents(self.center, extents)
+    }
+}
+
+impl From<QAabb2> for QObb2 {
+    fn from(aabb: QAabb2) -> Self {
+        Self::new(aabb.center(), aabb.half_extents(), QDir::default())
+    }
+}
@@ -0,0 +1,249 @@
+use crate::prelude::*;
+use crate::dir::QDir;
+use crate::vec2::QVec2;
+
+/// Values that can be eased towards a target, frame-rate independently.
+pub trait QSmooth: Copy {
+    /// Rate of change carried between calls of `smooth_damp`.
+    type Velocity: Copy + Default;
+
+    /// See [`decay_towards`].
+    #[must_use]
+    fn decay_towards(self, target: Self, half_life: Q64, dt: Q64) -> Self;
+
+    /// See [`smooth_damp`].
+    #[must_use]
+    fn smooth_damp(self, target: Self, velocity: &mut Self::Velocity, smooth_time: Q64, dt: Q64) -> Self;
+}
+
+/// Moves `current` towards `target` by exponential decay, closing half of the gap every
+/// `half_life` seconds.
+///
+/// Many small steps land where one big step of the same total time does, up to rounding, and the
+/// target is never overshot. A zero half-life snaps to the target.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::smooth::decay_towards;
+///
+/// let mut fast = q64!(0);
+/// for _ in 0..60 {
+///     fast = decay_towards(fast, q64!(100), q64!(0.25), q64!(1) / 60);
+/// }
+/// let slow = decay_towards(q64!(0), q64!(100), q64!(0.25), q64!(1));
+/// assert!((slow - q64!(93.75)).abs() <= q64!(0.000001));
+/// assert!((fast - slow).abs() <= q64!(0.00001));
+/// ```
+///
+/// # Panics
+///
+/// Will panic if `half_life` or `dt` is negative.
+///
+/// SAT
+#[inline]
+#[must_use]
+pub fn decay_towards<T: QSmooth>(current: T, target: T, half_life: Q64, dt: Q64) -> T {
+    current.decay_towards(target, half_life, dt)
+}
+
+/// Moves `current` towards `target` like a critically damped spring that settles in about
+/// `smooth_time` seconds, updating `velocity` for the next call.
+///
+/// The spring is solved exactly over `dt`, so the motion doesn't depend on the frame rate, and it
+/// stops at the target instead of overshooting it. Start from a default velocity; a zero
+/// smooth time snaps to the target and zeroes the velocity.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::smooth::smooth_damp;
+/// use qmath::vec2::QVec2;
+///
+/// let target = qvec2!(10, -4);
+/// let mut camera = QVec2::ZERO;
+/// let mut velocity = QVec2::ZERO;
+/// for _ in 0..30 {
+///     camera = smooth_damp(camera, target, &mut velocity, q64!(0.3), q64!(1) / 60);
+/// }
+/// assert!(camera.distance(target) < q64!(2));
+/// assert!(velocity.length() > q64!(0));
+/// for _ in 0..300 {
+///     camera = smooth_damp(camera, target, &mut velocity, q64!(0.3), q64!(1) / 60);
+/// }
+/// assert!(camera.distance(target) < q64!(0.0001));
+///
+/// // A second at 60 or at 15 frames per second ends up in the same place.
+/// let (mut a, mut speed_a) = (q64!(0), q64!(0));
+/// for _ in 0..60 {
+///     a = smooth_damp(a, q64!(10), &mut speed_a, q64!(0.5), q64!(1) / 60);
+/// }
+/// let (mut b, mut speed_b) = (q64!(0), q64!(0));
+/// for _ in 0..15 {
+///     b = smooth_damp(b, q64!(10), &mut speed_b, q64!(0.5), q64!(1) / 15);
+/// }
+/// assert!((a - b).abs() < q64!(0.00001));
+/// assert!((speed_a - speed_b).abs() < q64!(0.00001));
+/// ```
+///
+/// # Panics
+///
+/// Will panic if `smooth_time` or `dt` is negative.
+///
+/// SAT
+#[inline]
+#[must_use]
+pub fn smooth_damp<T: QSmooth>(current: T, target: T, velocity: &mut T::Velocity, smooth_time: Q64, dt: Q64) -> T {
+    current.smooth_damp(target, velocity, smooth_time, dt)
+}
+
+/// Returns the fraction of the gap left after `dt` seconds of decay.
+///
+/// SAT
+fn decay_factor(half_life: Q64, dt: Q64) -> Q64 {
+    assert!(half_life >= Q64::ZERO, "[smooth::decay_towards] Expected half_life >= 0.");
+    assert!(dt >= Q64::ZERO, "[smooth::decay_towards] Expected dt >= 0.");
+    if half_life == Q64::ZERO {
+        return Q64::ZERO;
+    }
+    (-Q64::LN_2.saturating_mul(dt.saturating_div(half_life))).exp()
+}
+
+/// Returns the stiffness and the decay over `dt` of the spring, or `None` if it should snap.
+///
+/// SAT
+fn spring(smooth_time: Q64, dt: Q64) -> Option<(Q64, Q64)> {
+    assert!(smooth_time >= Q64::ZERO, "[smooth::smooth_damp] Expected smooth_time >= 0.");
+    assert!(dt >= Q64::ZERO, "[smooth::smooth_damp] Expected dt >= 0.");
+    if smooth_time == Q64::ZERO {
+        return None;
+    }
+    let omega = Q64::TWO.saturating_div(smooth_time);
+    Some((omega, (-omega.saturating_mul(dt)).exp()))
+}
+
+/// Advances the offset from the target and its velocity along one axis.
+///
+/// SAT
+fn spring_axis(offset: Q64, velocity: Q64, omega: Q64, decay: Q64, dt: Q64) -> (Q64, Q64) {
+    let temp = velocity.saturating_add(omega.saturating_mul(offset)).saturating_mul(dt);
+    let velocity = velocity.saturating_sub(omega.saturating_mul(temp)).saturating_mul(decay);
+    (offset.saturating_add(temp).saturating_mul(decay), velocity)
+}
+
+impl QSmooth for Q64 {
+    type Velocity = Q64;
+
+    /// SAT
+    fn decay_towards(self, target: Self, half_life: Q64, dt: Q64) -> Self {
+        let factor = decay_factor(half_life, dt);
+        target.saturating_add(self.saturating_sub(target).saturating_mul(factor))
+    }
+
+    /// SAT
+    fn smooth_damp(self, target: Self, velocity: &mut Q64, smooth_time: Q64, dt: Q64) -> Self {
+        let Some((omega, decay)) = spring(smooth_time, dt) else {
+            *velocity = Q64::ZERO;
+            return target;
+        };
+        if dt == Q64::ZERO {
+            return self;
+        }
+        let change = self.saturating_sub(target);
+        let (offset, new_velocity) = spring_axis(change, *velocity, omega, decay, dt);
+        // Overshot if the new offset points the other way from the old one.
+        if change.signum() * offset.signum() < Q64::ZERO {
+            *velocity = Q64::ZERO;
+            return target;
+        }
+        *velocity = new_velocity;
+        target.saturating_add(offset)
+    }
+}
+
+impl QSmooth for QVec2 {
+    type Velocity = QVec2;
+
+    /// SAT
+    fn decay_towards(self, target: Self, half_life: Q64, dt: Q64) -> Self {
+        let factor = decay_factor(half_life, dt);
+        target.saturating_add(self.saturating_sub(target).saturating_mul_num(factor))
+    }
+
+    /// SAT
+    fn smooth_damp(self, target: Self, velocity: &mut QVec2, smooth_time: Q64, dt: Q64) -> Self {
+        let Some((omega, decay)) = spring(smooth_time, dt) else {
+            *velocity = QVec2::ZERO;
+            return target;
+        };
+        if dt == Q64::ZERO {
+            return self;
+        }
+        let change = self.saturating_sub(target);
+        let (x, velocity_x) = spring_axis(change.x, velocity.x, omega, decay, dt);
+        let (y, velocity_y) = spring_axis(change.y, velocity.y, omega, decay, dt);
+        // Overshot if the new offset points the other way from the old one.
+        if change.x.saturating_mul(x).saturating_add(change.y.saturating_mul(y)) < Q64::ZERO {
+            *velocity = QVec2::ZERO;
+            return target;
+        }
+        *velocity = QVec2::new(velocity_x, velocity_y);
+        target.saturating_add(QVec2::new(x, y))
+    }
+}
+
+/// Returns the signed angle from `from` to `to` the short way round, in `[-PI, PI)`.
+fn shortest_angle(from: QDir, to: QDir) -> Q64 {
+    let delta = to.angle() - from.angle();
+    if delta >= Q64::PI {
+        delta - Q64::TWO_PI
+    } else if delta < Q64::NEG_PI {
+        delta + Q64::TWO_PI
+    } else {
+        delta
+    }
+}
+
+impl QSmooth for QDir {
+    /// Angular velocity, in radians per second.
+    type Velocity = Q64;
+
+    /// Turns the short way round.
+    ///
+    /// SAT
+    fn decay_towards(self, target: Self, half_life: Q64, dt: Q64) -> Self {
+        let delta = shortest_angle(self, target);
+        let angle = Q64::ZERO.decay_towards(delta, half_life, dt);
+        QDir::new_from_angle(self.angle() + angle)
+    }
+
+    /// Turns the short way round.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::dir::QDir;
+    /// use qmath::smooth::smooth_damp;
+    ///
+    /// // From just below a full turn to just above zero, through zero.
+    /// let target = QDir::new(q64!(0.1));
+    /// let mut heading = QDir::new(Q64::TWO_PI - q64!(0.1));
+    /// let mut spin = Q64::ZERO;
+    /// heading = smooth_damp(heading, target, &mut spin, q64!(0.2), q64!(1) / 60);
+    /// assert!(spin > Q64::ZERO);
+    /// for _ in 0..120 {
+    ///     heading = smooth_damp(heading, target, &mut spin, q64!(0.2), q64!(1) / 60);
+    /// }
+    /// assert!((heading.angle() - q64!(0.1)).abs() < q64!(0.0001));
+    /// ```
+    ///
+    /// SAT
+    fn smooth_damp(self, target: Self, velocity: &mut Q64, smooth_time: Q64, dt: Q64) -> Self {
+        let delta = shortest_angle(self, target);
+        let angle = Q64::ZERO.smooth_damp(delta, velocity, smooth_time, dt);
+        QDir::new_from_angle(self.angle() + angle)
+    }
+}
@@ -0,0 +1,259 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A handle to an item of a [`QSpatialHash`].
+///
+/// Keys of removed items are never handed out again by the same hash, so a stale key is simply
+/// not found. After about four billion removals from one slot, that slot stays empty for good
+/// rather than wrap back to a generation an old key might still hold.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct QSpatialKey {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    /// Counts the removals from this slot. At `u32::MAX` the slot is left off the free list.
+    generation: u32,
+    item: Option<(QVec2, T)>,
+}
+
+/// A uniform grid of square cells bucketing items by position.
+///
+/// Cells live in a `BTreeMap` keyed by their floored coordinates, so only occupied cells use
+/// memory and every query visits cells row by row, bottom to top and left to right, and items in
+/// a cell in insertion order. Nothing depends on a hash seed, which keeps lockstep simulations in
+/// sync.
+#[derive(Debug, Clone)]
+pub struct QSpatialHash<T> {
+    cell_size: Q64,
+    /// Items of each cell, keyed by `(y, x)` of the cell so that iteration is row-major.
+    cells: BTreeMap<(Q64, Q64), Vec<QSpatialKey>>,
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> QSpatialHash<T> {
+    /// Creates an empty spatial hash with square cells of side `cell_size`.
+    ///
+    /// A cell size close to the typical query radius works best.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `cell_size` is not positive.
+    #[must_use]
+    pub fn new(cell_size: Q64) -> Self {
+        assert!(cell_size > Q64::ZERO, "[QSpatialHash::new] Expected cell_size > 0.");
+        Self { cell_size, cells: BTreeMap::new(), slots: Vec::new(), free: Vec::new(), len: 0 }
+    }
+
+    /// Returns the side of the cells.
+    #[inline]
+    #[must_use]
+    pub fn cell_size(&self) -> Q64 {
+        self.cell_size
+    }
+
+    /// Returns the number of items.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there are no items.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the coordinates of the cell containing `position`.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn cell_of(&self, position: QVec2) -> QVec2 {
+        position.saturating_div_num(self.cell_size).floor()
+    }
+
+    /// Inserts `value` at `position` and returns its key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::aabb::QAabb2;
+    /// use qmath::spatial_hash::QSpatialHash;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut hash = QSpatialHash::new(q64!(2));
+    /// let a = hash.insert(qvec2!(1, 1), 'a');
+    /// let b = hash.insert(qvec2!(5, 1), 'b');
+    /// let c = hash.insert(qvec2!(1.5, 1.5), 'c');
+    ///
+    /// let found: Vec<_> = hash.query_radius(qvec2!(0, 0), q64!(3)).map(|(_, v)| *v).collect();
+    /// assert_eq!(found, ['a', 'c']);
+    ///
+    /// assert_eq!(hash.remove(a), Some('a'));
+    /// assert_eq!(hash.remove(a), None);
+    /// assert!(hash.update(b, qvec2!(0.5, 0.5)));
+    /// let found: Vec<_> = hash.query_aabb(QAabb2::new(qvec2!(0, 0), qvec2!(2, 2))).map(|(k, _)| k).collect();
+    /// assert_eq!(found, [c, b]);
+    /// ```
+    pub fn insert(&mut self, position: QVec2, value: T) -> QSpatialKey {
+        let key = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.item = Some((position, value));
+                QSpatialKey { index, generation: slot.generation }
+            }
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("[QSpatialHash::insert] Too many items.");
+                self.slots.push(Slot { generation: 0, item: Some((position, value)) });
+                QSpatialKey { index, generation: 0 }
+            }
+        };
+        self.link(key, position);
+        self.len += 1;
+        key
+    }
+
+    /// Removes the item of `key` and returns its value, or `None` if it isn't present.
+    pub fn remove(&mut self, key: QSpatialKey) -> Option<T> {
+        let position = self.position(key)?;
+        self.unlink(key, position);
+        let slot = &mut self.slots[key.index as usize];
+        let (_, value) = slot.item.take()?;
+        slot.generation += 1;
+        if slot.generation < u32::MAX {
+            self.free.push(key.index);
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Moves the item of `key` to `position`, returning `false` if it isn't present.
+    ///
+    /// An item staying in its cell keeps its place in the cell order.
+    pub fn update(&mut self, key: QSpatialKey, position: QVec2) -> bool {
+        let Some(old) = self.position(key) else {
+            return false;
+        };
+        if self.cell_of(old) != self.cell_of(position) {
+            self.unlink(key, old);
+            self.link(key, position);
+        }
+        if let Some((p, _)) = &mut self.slots[key.index as usize].item {
+            *p = position;
+        }
+        true
+    }
+
+    /// Removes all items. Keys handed out before stay invalid.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.free.clear();
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.item.take().is_some() {
+                slot.generation += 1;
+            }
+            if slot.generation < u32::MAX {
+                self.free.push(index as u32);
+            }
+        }
+        // Hands out the lowest indices first.
+        self.free.reverse();
+        self.len = 0;
+    }
+
+    /// Returns the position of the item of `key`.
+    #[must_use]
+    pub fn position(&self, key: QSpatialKey) -> Option<QVec2> {
+        self.item(key).map(|(position, _)| *position)
+    }
+
+    /// Returns the value of the item of `key`.
+    #[must_use]
+    pub fn get(&self, key: QSpatialKey) -> Option<&T> {
+        self.item(key).map(|(_, value)| value)
+    }
+
+    /// Returns the value of the item of `key` mutably.
+    #[must_use]
+    pub fn get_mut(&mut self, key: QSpatialKey) -> Option<&mut T> {
+        let slot = self.slots.get_mut(key.index as usize)?;
+        if slot.generation != key.generation {
+            return None;
+        }
+        slot.item.as_mut().map(|(_, value)| value)
+    }
+
+    /// Returns whether the item of `key` is present.
+    #[inline]
+    #[must_use]
+    pub fn contains_key(&self, key: QSpatialKey) -> bool {
+        self.item(key).is_some()
+    }
+
+    /// Iterates over all items by key, in the order of [`QSpatialKey`].
+    pub fn iter(&self) -> impl Iterator<Item = (QSpatialKey, QVec2, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let (position, value) = slot.item.as_ref()?;
+            Some((QSpatialKey { index: index as u32, generation: slot.generation }, *position, value))
+        })
+    }
+
+    /// Iterates over the items whose position is inside `aabb`, boundary included.
+    pub fn query_aabb(&self, aabb: QAabb2) -> impl Iterator<Item = (QSpatialKey, &T)> + '_ {
+        let min = self.cell_of(aabb.min);
+        let max = self.cell_of(aabb.max);
+        self.cells
+            .range((min.y, min.x)..=(max.y, max.x))
+            .filter(move |((_, x), _)| *x >= min.x && *x <= max.x)
+            .flat_map(|(_, keys)| keys.iter())
+            .filter_map(move |key| {
+                let (position, value) = self.item(*key)?;
+                aabb.contains_point(*position).then_some((*key, value))
+            })
+    }
+
+    /// Iterates over the items within `radius` of `center`, boundary included.
+    ///
+    /// SAT
+    pub fn query_radius(&self, center: QVec2, radius: Q64) -> impl Iterator<Item = (QSpatialKey, &T)> + '_ {
+        let radius_squared = radius.saturating_mul(radius);
+        let aabb = QAabb2::new(center.saturating_sub_num(radius), center.saturating_add_num(radius));
+        self.query_aabb(aabb).filter(move |(key, _)| {
+            self.position(*key).is_some_and(|p| p.distance_squared(center) <= radius_squared)
+        })
+    }
+
+    fn item(&self, key: QSpatialKey) -> Option<&(QVec2, T)> {
+        let slot = self.slots.get(key.index as usize)?;
+        if slot.generation != key.generation {
+            return None;
+        }
+        slot.item.as_ref()
+    }
+
+    fn link(&mut self, key: QSpatialKey, position: QVec2) {
+        let cell = self.cell_of(position);
+        self.cells.entry((cell.y, cell.x)).or_default().push(key);
+    }
+
+    fn unlink(&mut self, key: QSpatialKey, position: QVec2) {
+        let cell = self.cell_of(position);
+        if let Some(keys) = self.cells.get_mut(&(cell.y, cell.x)) {
+            keys.retain(|k| *k != key);
+            if keys.is_empty() {
+                self.cells.remove(&(cell.y, cell.x));
+            }
+        }
+    }
+}
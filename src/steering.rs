@@ -0,0 +1,343 @@
+use crate::prelude::*;
+use crate::dir::QDir;
+use crate::rng::QRng;
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+
+/// A steered agent, such as a unit or a boid.
+///
+/// The behaviours return steering forces limited to `max_force`, to be summed or weighted by the
+/// caller and applied with [`QAgent::apply`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QAgent {
+    pub position: QVec2,
+    pub velocity: QVec2,
+    /// Facing, kept along the velocity while moving.
+    pub heading: QDir,
+    pub max_speed: Q64,
+    pub max_force: Q64,
+}
+
+/// State of the wander behaviour: a point drifting around a circle ahead of the agent.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QWander {
+    /// Distance from the agent to the center of the circle.
+    pub distance: Q64,
+    pub radius: Q64,
+    /// Largest change of the angle on the circle per call, in radians.
+    pub jitter: Q64,
+    /// Angle of the point on the circle, relative to the heading.
+    pub angle: Q64,
+}
+
+impl QWander {
+    /// Creates a wander state with the point straight ahead.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if any argument is negative.
+    #[inline]
+    #[must_use]
+    pub fn new(distance: Q64, radius: Q64, jitter: Q64) -> Self {
+        assert!(distance >= Q64::ZERO && radius >= Q64::ZERO && jitter >= Q64::ZERO, "[QWander::new] Expected non-negative parameters.");
+        Self { distance, radius, jitter, angle: Q64::ZERO }
+    }
+}
+
+/// Returns `v` scaled to `length`, or zero if `v` is zero.
+///
+/// SAT
+fn with_length(v: QVec2, length: Q64) -> QVec2 {
+    v.try_normalize().map_or(QVec2::ZERO, |unit| unit.saturating_mul_num(length))
+}
+
+/// Returns `v` shortened to at most `length`.
+///
+/// Measured along its own direction, as `QVec2::length` saturates for long vectors.
+///
+/// SAT
+fn truncate(v: QVec2, length: Q64) -> QVec2 {
+    match v.try_normalize() {
+        Some(unit) if v.dot(unit) > length => unit.saturating_mul_num(length),
+        _ => v,
+    }
+}
+
+impl QAgent {
+    /// Creates an agent at rest facing `QVec2::X`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `max_speed` or `max_force` is negative.
+    #[inline]
+    #[must_use]
+    pub fn new(position: QVec2, max_speed: Q64, max_force: Q64) -> Self {
+        assert!(max_speed >= Q64::ZERO, "[QAgent::new] Expected max_speed >= 0.");
+        assert!(max_force >= Q64::ZERO, "[QAgent::new] Expected max_force >= 0.");
+        Self { position, velocity: QVec2::ZERO, heading: QDir::default(), max_speed, max_force }
+    }
+
+    /// Returns the force that turns the velocity into `desired`.
+    ///
+    /// SAT
+    fn steer(&self, desired: QVec2) -> QVec2 {
+        truncate(desired.saturating_sub(self.velocity), self.max_force)
+    }
+
+    /// Steers at full speed towards `target`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::steering::QAgent;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let agent = QAgent::new(QVec2::ZERO, q64!(4), q64!(10));
+    /// assert_eq!(agent.seek(qvec2!(3, 0)), qvec2!(4, 0));
+    /// assert_eq!(agent.flee(qvec2!(3, 0)), qvec2!(-4, 0));
+    ///
+    /// // Far targets are no different.
+    /// let lazy = QAgent::new(QVec2::ZERO, q64!(4), q64!(1000));
+    /// assert_eq!(lazy.seek(qvec2!(100000, 0)), qvec2!(4, 0));
+    /// assert!(lazy.seek(qvec2!(-300000, 400000)).abs_diff_eq(qvec2!(-2.4, 3.2), q64!(0.000001)));
+    /// ```
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn seek(&self, target: QVec2) -> QVec2 {
+        self.steer(with_length(target.saturating_sub(self.position), self.max_speed))
+    }
+
+    /// Steers at full speed away from `target`.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn flee(&self, target: QVec2) -> QVec2 {
+        self.steer(with_length(self.position.saturating_sub(target), self.max_speed))
+    }
+
+    /// Steers towards `target`, slowing down linearly within `slowing_radius` to stop on it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::steering::QAgent;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut agent = QAgent::new(qvec2!(-10, 0), q64!(4), q64!(20));
+    /// for _ in 0..1200 {
+    ///     let force = agent.arrive(QVec2::ZERO, q64!(3));
+    ///     agent.apply(force, q64!(1) / 60);
+    /// }
+    /// assert!(agent.position.length() < q64!(0.01));
+    /// assert!(agent.velocity.length() < q64!(0.01));
+    ///
+    /// let far = QAgent::new(QVec2::ZERO, q64!(4), q64!(1000));
+    /// assert_eq!(far.arrive(qvec2!(100000, 0), q64!(3)), qvec2!(4, 0));
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn arrive(&self, target: QVec2, slowing_radius: Q64) -> QVec2 {
+        let offset = target.saturating_sub(self.position);
+        let distance = offset.length();
+        let speed = if distance < slowing_radius {
+            self.max_speed.saturating_mul(distance).saturating_div(slowing_radius)
+        } else {
+            self.max_speed
+        };
+        self.steer(with_length(offset, speed))
+    }
+
+    /// Returns where `target` will be when this agent could reach it at full speed.
+    ///
+    /// SAT
+    fn predict(&self, target: &QAgent) -> QVec2 {
+        let distance = self.position.distance(target.position);
+        let time = if self.max_speed == Q64::ZERO { Q64::ZERO } else { distance.saturating_div(self.max_speed) };
+        target.position.saturating_add(target.velocity.saturating_mul_num(time))
+    }
+
+    /// Seeks where `target` is heading.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::steering::QAgent;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let hunter = QAgent::new(QVec2::ZERO, q64!(2), q64!(100));
+    /// let mut prey = QAgent::new(qvec2!(4, 0), q64!(2), q64!(1));
+    /// prey.velocity = qvec2!(0, 1);
+    /// // The prey will be at (4, 2) in the two seconds it takes to get there.
+    /// let force = hunter.pursue(&prey);
+    /// assert!(force.y > Q64::ZERO);
+    /// assert_eq!(force, hunter.seek(qvec2!(4, 2)));
+    /// assert_eq!(hunter.evade(&prey), hunter.flee(qvec2!(4, 2)));
+    /// ```
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn pursue(&self, target: &QAgent) -> QVec2 {
+        self.seek(self.predict(target))
+    }
+
+    /// Flees from where `target` is heading.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn evade(&self, target: &QAgent) -> QVec2 {
+        self.flee(self.predict(target))
+    }
+
+    /// Seeks a point that drifts randomly around a circle ahead of the agent.
+    ///
+    /// The state and the generator are advanced, so agents replaying the same seed wander the same
+    /// way on every machine.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::rng::QRng;
+    /// use qmath::steering::{QAgent, QWander};
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let run = |seed| {
+    ///     let mut rng = QRng::from_seed(seed);
+    ///     let mut agent = QAgent::new(QVec2::ZERO, q64!(2), q64!(4));
+    ///     let mut wander = QWander::new(q64!(2), q64!(1), q64!(0.5));
+    ///     for _ in 0..200 {
+    ///         let force = agent.wander(&mut wander, &mut rng);
+    ///         agent.apply(force, q64!(1) / 30);
+    ///     }
+    ///     agent
+    /// };
+    /// assert_eq!(run(7), run(7));
+    /// assert_ne!(run(7).position, run(8).position);
+    /// assert!(run(7).velocity.length() <= q64!(2));
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn wander(&self, state: &mut QWander, rng: &mut QRng) -> QVec2 {
+        state.angle = (state.angle + rng.uniform(-state.jitter, state.jitter)) % Q64::TWO_PI;
+        let heading = self.heading.to_vec();
+        let center = self.position.saturating_add(heading.saturating_mul_num(state.distance));
+        let offset = QVec2::from_angle(self.heading.angle() + state.angle).saturating_mul_num(state.radius);
+        self.seek(center.saturating_add(offset))
+    }
+
+    /// Returns the neighbors other than this agent within `radius`.
+    fn neighbors<'a>(&'a self, agents: &'a [QAgent], radius: Q64) -> impl Iterator<Item = &'a QAgent> + 'a {
+        let radius_squared = radius.saturating_mul(radius);
+        agents.iter().filter(move |other| *other != self && self.position.distance_squared(other.position) <= radius_squared)
+    }
+
+    /// Steers away from the neighbors within `radius`, more strongly from closer ones.
+    ///
+    /// Agents equal to this one, such as itself, are skipped, so the whole flock can be passed.
+    /// Neighbors on the same spot push along `QVec2::X`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::steering::QAgent;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut flock = [
+    ///     QAgent::new(qvec2!(0, 0), q64!(1), q64!(1)),
+    ///     QAgent::new(qvec2!(1, 0), q64!(1), q64!(1)),
+    ///     QAgent::new(qvec2!(0, 1), q64!(1), q64!(1)),
+    ///     QAgent::new(qvec2!(20, 0), q64!(1), q64!(1)),
+    /// ];
+    /// flock[1].velocity = qvec2!(0, 1);
+    /// flock[2].velocity = qvec2!(0, 1);
+    ///
+    /// let me = flock[0];
+    /// let away = me.separation(&flock, q64!(2));
+    /// assert!(away.x < Q64::ZERO && away.y < Q64::ZERO);
+    /// assert_eq!(me.alignment(&flock, q64!(2)), qvec2!(0, 1));
+    /// assert_eq!(me.cohesion(&flock, q64!(2)), me.seek(qvec2!(0.5, 0.5)));
+    /// // The far agent is out of range.
+    /// assert_eq!(me.cohesion(&flock[3..], q64!(2)), QVec2::ZERO);
+    /// ```
+    ///
+    /// SAT
+    #[must_use]
+    pub fn separation(&self, agents: &[QAgent], radius: Q64) -> QVec2 {
+        let mut push = QVec2::ZERO;
+        for other in self.neighbors(agents, radius) {
+            let offset = self.position.saturating_sub(other.position);
+            let distance_squared = offset.length_squared();
+            let away = if distance_squared == Q64::ZERO { QVec2::X } else { offset.saturating_div_num(distance_squared) };
+            push = push.saturating_add(away);
+        }
+        if push == QVec2::ZERO {
+            return QVec2::ZERO;
+        }
+        self.steer(with_length(push, self.max_speed))
+    }
+
+    /// Steers to match the average velocity of the neighbors within `radius`, at full speed.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn alignment(&self, agents: &[QAgent], radius: Q64) -> QVec2 {
+        let sum = self.neighbors(agents, radius).fold(QVec2::ZERO, |sum, other| sum.saturating_add(other.velocity));
+        if sum == QVec2::ZERO {
+            return QVec2::ZERO;
+        }
+        self.steer(with_length(sum, self.max_speed))
+    }
+
+    /// Seeks the average position of the neighbors within `radius`.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn cohesion(&self, agents: &[QAgent], radius: Q64) -> QVec2 {
+        let (sum, count) = self
+            .neighbors(agents, radius)
+            .fold((QVec2::ZERO, 0i64), |(sum, count), other| (sum.saturating_add(other.position), count + 1));
+        if count == 0 {
+            return QVec2::ZERO;
+        }
+        self.seek(sum.saturating_div_num(Q64::from_num(count)))
+    }
+
+    /// Applies a steering `force` for `dt` seconds with unit mass, limiting it to `max_force` and
+    /// the speed to `max_speed`, and turns the heading along the velocity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::steering::QAgent;
+    /// use qmath::vec2::QVec2;
+    ///
+    /// let mut agent = QAgent::new(QVec2::ZERO, q64!(4), q64!(2));
+    /// agent.apply(qvec2!(0, 600000), q64!(1));
+    /// assert_eq!(agent.velocity, qvec2!(0, 2));
+    /// agent.velocity = qvec2!(-90000, 0);
+    /// agent.apply(QVec2::ZERO, q64!(1));
+    /// assert_eq!(agent.velocity, qvec2!(-4, 0));
+    /// ```
+    ///
+    /// SAT
+    pub fn apply(&mut self, force: QVec2, dt: Q64) {
+        let force = truncate(force, self.max_force);
+        self.velocity = truncate(self.velocity.saturating_add(force.saturating_mul_num(dt)), self.max_speed);
+        self.position = self.position.saturating_add(self.velocity.saturating_mul_num(dt));
+        if self.velocity != QVec2::ZERO {
+            self.heading = QDir::new_from_vec(self.velocity);
+        }
+    }
+}
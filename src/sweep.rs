@@ -0,0 +1,142 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::ray::{QRay2, QRayHit, QRaycast, QSegment2};
+use crate::shapes::{QCapsule2, QCircle};
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+
+/// The first contact of a shape moving along a displacement.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QSweepHit {
+    /// Fraction of the displacement travelled before the contact, in `[0, 1]`.
+    pub t: Q64,
+    /// Unit normal of the obstacle at the contact, pointing towards the moving shape.
+    pub normal: QVec2,
+}
+
+impl From<QRayHit> for QSweepHit {
+    fn from(hit: QRayHit) -> Self {
+        Self { t: hit.t, normal: hit.normal }
+    }
+}
+
+/// Casts the path of a point moving by `motion` against `shape`, or `None` if it doesn't move.
+fn sweep_point<S: QRaycast + ?Sized>(start: QVec2, motion: QVec2, shape: &S) -> Option<QSweepHit> {
+    if motion == QVec2::ZERO {
+        return None;
+    }
+    QRay2::new(start, motion).cast(shape, Q64::ONE).map(QSweepHit::from)
+}
+
+/// Returns when `circle`, moving by `motion`, first touches `segment`.
+///
+/// The circle is shrunk to its center and the segment inflated to a capsule, so the query is
+/// a raycast and a thin wall can't be skipped however fast the circle moves. A circle already
+/// touching the segment hits at `t = 0` with the normal opposing `motion`. Returns `None` if
+/// `motion` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::ray::QSegment2;
+/// use qmath::shapes::QCircle;
+/// use qmath::sweep::sweep_circle_segment;
+/// use qmath::vec2::QVec2;
+///
+/// let wall = QSegment2::new(qvec2!(5, -1), qvec2!(5, 1));
+/// let bullet = QCircle::new(qvec2!(0, 0), q64!(0.25));
+///
+/// // Moves 20 units in one step, straight through the wall without a sweep.
+/// let hit = sweep_circle_segment(&bullet, qvec2!(20, 0), &wall).unwrap();
+/// assert!((hit.t - q64!(0.2375)).abs() <= Q64::EPS);
+/// assert_eq!(hit.normal, QVec2::NEG_X);
+///
+/// // Clips the top end of the wall.
+/// let hit = sweep_circle_segment(&bullet, qvec2!(10, 2.5), &wall).unwrap();
+/// assert!(hit.normal.x < 0 && hit.normal.y > 0);
+///
+/// assert!(sweep_circle_segment(&bullet, qvec2!(4, 0), &wall).is_none());
+/// ```
+#[must_use]
+pub fn sweep_circle_segment(circle: &QCircle, motion: QVec2, segment: &QSegment2) -> Option<QSweepHit> {
+    sweep_point(circle.center, motion, &QCapsule2::new(segment.a, segment.b, circle.radius))
+}
+
+/// Returns when `circle`, moving by `motion`, first touches `aabb`.
+///
+/// The box is inflated by the radius into a rounded box, made of the box grown along each axis
+/// and a circle at each corner, so hits on the faces are exact. A circle already touching the box
+/// hits at `t = 0` with the normal opposing `motion`. Returns `None` if `motion` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::aabb::QAabb2;
+/// use qmath::shapes::QCircle;
+/// use qmath::sweep::sweep_circle_aabb;
+/// use qmath::vec2::QVec2;
+///
+/// let floor = QAabb2::new(qvec2!(-10, -1), qvec2!(10, 0));
+/// let ball = QCircle::new(qvec2!(0, 4), q64!(1));
+///
+/// let hit = sweep_circle_aabb(&ball, qvec2!(0, -6), &floor).unwrap();
+/// assert_eq!((hit.t, hit.normal), (q64!(0.5), QVec2::Y));
+///
+/// // Rounding the corner of the floor.
+/// let hit = sweep_circle_aabb(&QCircle::new(qvec2!(12, 4), q64!(1)), qvec2!(-2, -6), &floor).unwrap();
+/// assert!(hit.normal.x > 0 && hit.normal.y > 0);
+///
+/// assert!(sweep_circle_aabb(&ball, qvec2!(0, 2), &floor).is_none());
+/// ```
+#[must_use]
+pub fn sweep_circle_aabb(circle: &QCircle, motion: QVec2, aabb: &QAabb2) -> Option<QSweepHit> {
+    let (rx, ry) = (QVec2::new(circle.radius, Q64::ZERO), QVec2::new(Q64::ZERO, circle.radius));
+    let grown_x = QAabb2::new(aabb.min.saturating_sub(rx), aabb.max.saturating_add(rx));
+    let grown_y = QAabb2::new(aabb.min.saturating_sub(ry), aabb.max.saturating_add(ry));
+    let corners = [aabb.min, QVec2::new(aabb.max.x, aabb.min.y), aabb.max, QVec2::new(aabb.min.x, aabb.max.y)];
+
+    let mut best = None;
+    let mut keep_nearest = |hit: Option<QSweepHit>| {
+        if let Some(hit) = hit
+            && best.is_none_or(|b: QSweepHit| hit.t < b.t)
+        {
+            best = Some(hit);
+        }
+    };
+    keep_nearest(sweep_point(circle.center, motion, &grown_x));
+    keep_nearest(sweep_point(circle.center, motion, &grown_y));
+    for corner in corners {
+        keep_nearest(sweep_point(circle.center, motion, &QCircle::new(corner, circle.radius)));
+    }
+    best
+}
+
+/// Returns when `moving`, moving by `motion`, first touches `other`.
+///
+/// `other` is grown by the half extents of `moving`, which is shrunk to its center. Boxes already
+/// touching hit at `t = 0` with the normal opposing `motion`. Returns `None` if `motion` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use qmath::prelude::*;
+/// use qmath::aabb::QAabb2;
+/// use qmath::sweep::sweep_aabbs;
+/// use qmath::vec2::QVec2;
+///
+/// let player = QAabb2::new(qvec2!(0, 0), qvec2!(1, 2));
+/// let wall = QAabb2::new(qvec2!(3, -5), qvec2!(3.5, 5));
+///
+/// let hit = sweep_aabbs(&player, qvec2!(8, 1), &wall).unwrap();
+/// assert_eq!((hit.t, hit.normal), (q64!(0.25), QVec2::NEG_X));
+///
+/// assert!(sweep_aabbs(&player, qvec2!(-8, 1), &wall).is_none());
+/// ```
+#[must_use]
+pub fn sweep_aabbs(moving: &QAabb2, motion: QVec2, other: &QAabb2) -> Option<QSweepHit> {
+    let half_extents = moving.half_extents();
+    let grown = QAabb2::new(other.min.saturating_sub(half_extents), other.max.saturating_add(half_extents));
+    sweep_point(moving.center(), motion, &grown)
+}
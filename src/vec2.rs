@@ -307,6 +307,25 @@ impl QVec2 {
         self.saturating_mul_num(self.length_recip())
     }
 
+    /// Returns `self` normalized to length 1.0, or `None` if its length rounds to zero.
+    ///
+    /// Long vectors are scaled down first, as `length()` saturates from about 46341. Divides by
+    /// the length rather than multiplying by its reciprocal, so that axis-aligned directions stay
+    /// exact.
+    ///
+    /// SAT
+    #[must_use]
+    pub(crate) fn try_normalize(self) -> Option<Self> {
+        // Keeps the squared length below 2^31.
+        let v = if self.x.unsigned_abs() >= 32768 || self.y.unsigned_abs() >= 32768 {
+            Self::new(self.x >> 16, self.y >> 16)
+        } else {
+            self
+        };
+        let length = v.length();
+        (length != Q64::ZERO).then(|| v.saturating_div_num(length))
+    }
+
     /// Returns whether `self` is length `1.0` or not.
     ///
     /// SAT
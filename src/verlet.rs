@@ -0,0 +1,331 @@
+use crate::prelude::*;
+use crate::aabb::QAabb2;
+use crate::shapes::QCircle;
+use crate::vec2::QVec2;
+use serde::{Deserialize, Serialize};
+
+/// Constraint iterations of a new simulation.
+const DEFAULT_ITERATIONS: u32 = 8;
+
+/// A point mass integrated by position Verlet.
+///
+/// The velocity is implicit, as the difference between the current and previous positions.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QParticle {
+    pub position: QVec2,
+    pub previous: QVec2,
+    /// Pinned particles don't move, but still pull on their constraints.
+    pub pinned: bool,
+}
+
+impl QParticle {
+    /// Creates a particle at rest.
+    #[inline]
+    #[must_use]
+    pub const fn new(position: QVec2) -> Self {
+        Self { position, previous: position, pinned: false }
+    }
+
+    /// Returns the displacement over the last step.
+    ///
+    /// SAT
+    #[inline]
+    #[must_use]
+    pub fn displacement(&self) -> QVec2 {
+        self.position.saturating_sub(self.previous)
+    }
+}
+
+/// Keeps two particles at a distance.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QStick {
+    pub a: usize,
+    pub b: usize,
+    pub length: Q64,
+    /// Fraction of the error corrected per iteration, from `0` for none to `1` for a rigid stick.
+    pub stiffness: Q64,
+}
+
+/// A solid obstacle that particles are pushed out of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QVerletCollider {
+    Aabb(QAabb2),
+    Circle(QCircle),
+}
+
+impl QVerletCollider {
+    /// Returns `point` moved to the closest point outside the collider.
+    ///
+    /// A point at the center of a circle is pushed up.
+    ///
+    /// SAT
+    #[must_use]
+    pub fn push_out(&self, point: QVec2) -> QVec2 {
+        match self {
+            QVerletCollider::Aabb(aabb) => {
+                if !aabb.contains_point(point) {
+                    return point;
+                }
+                // The nearest face wins, in the order left, right, bottom, top.
+                let exits = [
+                    (point.x.saturating_sub(aabb.min.x), QVec2::new(aabb.min.x, point.y)),
+                    (aabb.max.x.saturating_sub(point.x), QVec2::new(aabb.max.x, point.y)),
+                    (point.y.saturating_sub(aabb.min.y), QVec2::new(point.x, aabb.min.y)),
+                    (aabb.max.y.saturating_sub(point.y), QVec2::new(point.x, aabb.max.y)),
+                ];
+                exits.into_iter().fold(exits[0], |best, exit| if exit.0 < best.0 { exit } else { best }).1
+            }
+            QVerletCollider::Circle(circle) => {
+                let offset = point.saturating_sub(circle.center);
+                let distance_squared = offset.length_squared();
+                if distance_squared >= circle.radius.saturating_mul(circle.radius) {
+                    return point;
+                }
+                let distance = distance_squared.sqrt();
+                let dir = if distance == Q64::ZERO { QVec2::Y } else { offset.saturating_div_num(distance) };
+                circle.center.saturating_add(dir.saturating_mul_num(circle.radius))
+            }
+        }
+    }
+}
+
+/// A Verlet particle system with distance constraints, for ropes, cloth and soft bodies.
+///
+/// Each step moves the particles by their implicit velocity and gravity, then relaxes the sticks
+/// and pushes the particles out of the colliders a number of times, in index order. Everything is
+/// saturating fixed-point arithmetic, so the same inputs give the same bits everywhere, and even
+/// wildly stretched configurations clamp instead of overflowing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QVerlet {
+    pub gravity: QVec2,
+    /// Fraction of the velocity kept each step, `1` for none lost.
+    pub damping: Q64,
+    /// Relaxation iterations per step.
+    pub iterations: u32,
+    particles: Vec<QParticle>,
+    sticks: Vec<QStick>,
+    colliders: Vec<QVerletCollider>,
+}
+
+impl Default for QVerlet {
+    fn default() -> Self {
+        Self::new(QVec2::ZERO)
+    }
+}
+
+impl QVerlet {
+    /// Creates an empty simulation.
+    #[must_use]
+    pub fn new(gravity: QVec2) -> Self {
+        Self {
+            gravity,
+            damping: Q64::lit("0.99"),
+            iterations: DEFAULT_ITERATIONS,
+            particles: Vec::new(),
+            sticks: Vec::new(),
+            colliders: Vec::new(),
+        }
+    }
+
+    /// Adds a particle at rest at `position` and returns its index.
+    pub fn add_particle(&mut self, position: QVec2) -> usize {
+        self.particles.push(QParticle::new(position));
+        self.particles.len() - 1
+    }
+
+    /// Adds a rigid stick between two particles at their current distance and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a particle index is out of bounds.
+    pub fn add_stick(&mut self, a: usize, b: usize) -> usize {
+        let length = self.particles[a].position.distance(self.particles[b].position);
+        self.add_stick_with(a, b, length, Q64::ONE)
+    }
+
+    /// Adds a stick with the given rest length and stiffness and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a particle index is out of bounds, `length` is negative or `stiffness` is not
+    /// in `[0, 1]`.
+    pub fn add_stick_with(&mut self, a: usize, b: usize, length: Q64, stiffness: Q64) -> usize {
+        assert!(a < self.particles.len() && b < self.particles.len(), "[QVerlet::add_stick_with] Particle index out of bounds.");
+        assert!(length >= Q64::ZERO, "[QVerlet::add_stick_with] Expected length >= 0.");
+        assert!((Q64::ZERO..=Q64::ONE).contains(&stiffness), "[QVerlet::add_stick_with] Expected stiffness in [0, 1].");
+        self.sticks.push(QStick { a, b, length, stiffness });
+        self.sticks.len() - 1
+    }
+
+    /// Adds a collider and returns its index.
+    pub fn add_collider(&mut self, collider: QVerletCollider) -> usize {
+        self.colliders.push(collider);
+        self.colliders.len() - 1
+    }
+
+    /// Adds a cloth of `columns` by `rows` particles hanging down from `top_left`, `spacing`
+    /// apart and joined by sticks along the rows and columns. The top row is pinned.
+    ///
+    /// Returns the index of the first particle. The particle of column `c` and row `r` is at
+    /// `first + r * columns + c`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::shapes::QCircle;
+    /// use qmath::vec2::QVec2;
+    /// use qmath::verlet::{QVerlet, QVerletCollider};
+    ///
+    /// let mut sim = QVerlet::new(qvec2!(0, -10));
+    /// let first = sim.add_cloth(qvec2!(-2, 4), 9, 9, q64!(0.5));
+    /// let ball = QCircle::new(qvec2!(0, 0), q64!(1.5));
+    /// sim.add_collider(QVerletCollider::Circle(ball));
+    ///
+    /// let mut replay = sim.clone();
+    /// for _ in 0..120 {
+    ///     sim.step(q64!(1) / 60);
+    /// }
+    ///
+    /// // The top row hangs where it was pinned and nothing went through the ball.
+    /// assert_eq!(sim.particles()[first + 4].position, qvec2!(0, 4));
+    /// assert!(sim.particles().iter().all(|p| p.position.distance(ball.center) >= ball.radius - q64!(0.01)));
+    ///
+    /// for _ in 0..120 {
+    ///     replay.step(q64!(1) / 60);
+    /// }
+    /// assert_eq!(replay, sim);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `columns` or `rows` is zero.
+    pub fn add_cloth(&mut self, top_left: QVec2, columns: usize, rows: usize, spacing: Q64) -> usize {
+        assert!(columns > 0 && rows > 0, "[QVerlet::add_cloth] Expected at least one column and row.");
+        let first = self.particles.len();
+        for r in 0..rows {
+            for c in 0..columns {
+                let offset = QVec2::new(spacing.saturating_mul(Q64::from_num(c)), -spacing.saturating_mul(Q64::from_num(r)));
+                let index = self.add_particle(top_left.saturating_add(offset));
+                self.particles[index].pinned = r == 0;
+                if c > 0 {
+                    self.add_stick(index - 1, index);
+                }
+                if r > 0 {
+                    self.add_stick(index - columns, index);
+                }
+            }
+        }
+        first
+    }
+
+    /// Returns the particles, by index.
+    #[inline]
+    #[must_use]
+    pub fn particles(&self) -> &[QParticle] {
+        &self.particles
+    }
+
+    /// Returns the particles mutably, by index, to move or pin them.
+    #[inline]
+    #[must_use]
+    pub fn particles_mut(&mut self) -> &mut [QParticle] {
+        &mut self.particles
+    }
+
+    /// Returns the sticks, by index.
+    #[inline]
+    #[must_use]
+    pub fn sticks(&self) -> &[QStick] {
+        &self.sticks
+    }
+
+    /// Returns the colliders, by index.
+    #[inline]
+    #[must_use]
+    pub fn colliders(&self) -> &[QVerletCollider] {
+        &self.colliders
+    }
+
+    /// Advances the simulation by `dt` seconds.
+    ///
+    /// A rope pinned at one end keeps its length while swinging:
+    ///
+    /// ```
+    /// use qmath::prelude::*;
+    /// use qmath::vec2::QVec2;
+    /// use qmath::verlet::QVerlet;
+    ///
+    /// let mut sim = QVerlet::new(qvec2!(0, -10));
+    /// let anchor = sim.add_particle(qvec2!(0, 0));
+    /// sim.particles_mut()[anchor].pinned = true;
+    /// let mut previous = anchor;
+    /// for i in 1..=10 {
+    ///     let next = sim.add_particle(QVec2::new(Q64::from_num(i) / 4, Q64::ZERO));
+    ///     sim.add_stick(previous, next);
+    ///     previous = next;
+    /// }
+    ///
+    /// for _ in 0..60 {
+    ///     sim.step(q64!(1) / 60);
+    /// }
+    /// let end = sim.particles()[previous].position;
+    /// assert!(end.y < q64!(-1.5));
+    /// assert!(end.length() <= q64!(2.5) + q64!(0.05));
+    ///
+    /// // Absurd positions saturate rather than overflow.
+    /// sim.particles_mut()[previous].position = QVec2::MAX;
+    /// sim.step(q64!(1) / 60);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `dt` is negative.
+    ///
+    /// SAT
+    pub fn step(&mut self, dt: Q64) {
+        assert!(dt >= Q64::ZERO, "[QVerlet::step] Expected dt >= 0.");
+        let acceleration = self.gravity.saturating_mul_num(dt.saturating_mul(dt));
+        for particle in self.particles.iter_mut().filter(|p| !p.pinned) {
+            let velocity = particle.displacement().saturating_mul_num(self.damping);
+            particle.previous = particle.position;
+            particle.position = particle.position.saturating_add(velocity).saturating_add(acceleration);
+        }
+
+        for _ in 0..self.iterations {
+            for stick in &self.sticks {
+                relax(&mut self.particles, stick);
+            }
+            for particle in self.particles.iter_mut().filter(|p| !p.pinned) {
+                for collider in &self.colliders {
+                    particle.position = collider.push_out(particle.position);
+                }
+            }
+        }
+    }
+}
+
+/// Moves the particles of `stick` towards its rest length, sharing the correction between the
+/// particles that aren't pinned.
+///
+/// SAT
+fn relax(particles: &mut [QParticle], stick: &QStick) {
+    let (a, b) = (particles[stick.a], particles[stick.b]);
+    let delta = b.position.saturating_sub(a.position);
+    let distance = delta.length();
+    if distance == Q64::ZERO {
+        return;
+    }
+    let error = distance.saturating_sub(stick.length).saturating_div(distance).saturating_mul(stick.stiffness);
+    let correction = delta.saturating_mul_num(error);
+    match (a.pinned, b.pinned) {
+        (false, false) => {
+            let half = correction.saturating_mul_num(Q64::HALF);
+            particles[stick.a].position = a.position.saturating_add(half);
+            particles[stick.b].position = b.position.saturating_sub(half);
+        }
+        (false, true) => particles[stick.a].position = a.position.saturating_add(correction),
+        (true, false) => particles[stick.b].position = b.position.saturating_sub(correction),
+        (true, true) => {}
+    }
+}
@@ -0,0 +1,183 @@
+use crate::Q64;
+use core::cmp::Ordering;
+use core::ops::*;
+
+const LIMBS: usize = 5;
+
+/// A 320-bit two's complement integer.
+///
+/// Wide enough to hold exact sums of products of up to four differences of `Q64` raw bits, as the
+/// degree-4 terms of `incircle` need. Arithmetic wraps on overflow.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct I320([u64; LIMBS]);
+
+impl I320 {
+    pub const ZERO: Self = Self([0; LIMBS]);
+
+    /// Sign-extends an `i128`.
+    pub const fn from_i128(value: i128) -> Self {
+        let ext = if value < 0 { u64::MAX } else { 0 };
+        Self([value as u64, (value >> 64) as u64, ext, ext, ext])
+    }
+
+    /// Takes the raw bits of a `Q64`, so the value is scaled by `2^32`.
+    pub const fn from_bits(value: Q64) -> Self {
+        Self::from_i128(value.to_bits() as i128)
+    }
+
+    pub const fn is_negative(&self) -> bool {
+        self.0[LIMBS - 1] >> 63 == 1
+    }
+
+    /// Returns the ordering of `self` against zero.
+    pub fn signum(&self) -> Ordering {
+        if self.is_negative() {
+            Ordering::Less
+        } else if *self == Self::ZERO {
+            Ordering::Equal
+        } else {
+            Ordering::Greater
+        }
+    }
+
+    pub fn abs(self) -> Self {
+        if self.is_negative() { -self } else { self }
+    }
+
+    /// Converts to `i128`, saturating if out of range.
+    pub fn saturating_to_i128(&self) -> i128 {
+        let ext = if self.is_negative() { u64::MAX } else { 0 };
+        let fits = self.0[2..].iter().all(|limb| *limb == ext) && (self.0[1] >> 63 == ext >> 63);
+        if !fits {
+            return if self.is_negative() { i128::MIN } else { i128::MAX };
+        }
+        (self.0[0] as u128 | (self.0[1] as u128) << 64) as i128
+    }
+
+    /// Converts raw bits back to a `Q64`, saturating if out of range.
+    pub fn saturating_to_q64(&self) -> Q64 {
+        let bits = self.saturating_to_i128().clamp(i64::MIN as i128, i64::MAX as i128);
+        Q64::from_bits(bits as i64)
+    }
+
+    fn shl1(&mut self) {
+        for i in (1..LIMBS).rev() {
+            self.0[i] = (self.0[i] << 1) | (self.0[i - 1] >> 63);
+        }
+        self.0[0] <<= 1;
+    }
+
+    fn bit(&self, index: usize) -> u64 {
+        (self.0[index / 64] >> (index % 64)) & 1
+    }
+
+    /// Unsigned comparison of the limbs.
+    fn cmp_unsigned(&self, other: &Self) -> Ordering {
+        for i in (0..LIMBS).rev() {
+            match self.0[i].cmp(&other.0[i]) {
+                Ordering::Equal => continue,
+                ord => return ord,
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// Divides magnitudes by shift-and-subtract, returning `(quotient, remainder)`.
+    fn div_rem_unsigned(&self, divisor: &Self) -> (Self, Self) {
+        let mut quotient = Self::ZERO;
+        let mut remainder = Self::ZERO;
+        for index in (0..LIMBS * 64).rev() {
+            remainder.shl1();
+            remainder.0[0] |= self.bit(index);
+            if remainder.cmp_unsigned(divisor) != Ordering::Less {
+                remainder = remainder - *divisor;
+                quotient.0[index / 64] |= 1 << (index % 64);
+            }
+        }
+        (quotient, remainder)
+    }
+
+    /// Divides rounding the quotient to the nearest integer, ties away from zero.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `divisor` is zero.
+    pub fn div_round(self, divisor: Self) -> Self {
+        assert!(divisor != Self::ZERO, "[I320::div_round] Division by zero.");
+        let negative = self.is_negative() != divisor.is_negative();
+        let divisor = divisor.abs();
+        let (mut quotient, remainder) = self.abs().div_rem_unsigned(&divisor);
+        let mut doubled = remainder;
+        doubled.shl1();
+        if doubled.cmp_unsigned(&divisor) != Ordering::Less {
+            quotient = quotient + Self::from_i128(1);
+        }
+        if negative { -quotient } else { quotient }
+    }
+}
+
+impl Add for I320 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let mut result = [0; LIMBS];
+        let mut carry = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (sum, c1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        Self(result)
+    }
+}
+
+impl Neg for I320 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        let mut inverted = self;
+        for limb in inverted.0.iter_mut() {
+            *limb = !*limb;
+        }
+        inverted + Self::from_i128(1)
+    }
+}
+
+impl Sub for I320 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for I320 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        // Wrapping schoolbook multiplication, correct for two's complement.
+        let mut result = [0_u64; LIMBS];
+        for i in 0..LIMBS {
+            let mut carry = 0_u128;
+            for j in 0..LIMBS - i {
+                let current = result[i + j] as u128 + self.0[i] as u128 * rhs.0[j] as u128 + carry;
+                result[i + j] = current as u64;
+                carry = current >> 64;
+            }
+        }
+        Self(result)
+    }
+}
+
+impl PartialOrd for I320 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for I320 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => self.cmp_unsigned(other),
+        }
+    }
+}